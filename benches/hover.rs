@@ -0,0 +1,44 @@
+//! Measures hovering over the Circle Drawer canvas with 10k circles.
+
+use std::hint::black_box;
+
+use criterion::{Criterion, criterion_group, criterion_main};
+use iced::Point;
+use iced_7guis::circle_drawer::{self, CANVAS_SIZE, Circle, CircleDrawer, Mouse, MouseEvent};
+
+const CIRCLES: u16 = 10_000;
+
+/// A 100 by 100 lattice of small circles covering the canvas.
+fn drawer() -> CircleDrawer {
+    let step = CANVAS_SIZE / 100.0;
+    let circles = (0..CIRCLES)
+        .map(|i| Circle {
+            center: Point::new(
+                f32::from(i % 100) * step + step / 2.0,
+                f32::from(i / 100) * step + step / 2.0,
+            ),
+            radius: 10.0,
+            ..Circle::default()
+        })
+        .collect();
+
+    CircleDrawer::with_circles(circles)
+}
+
+fn hover(c: &mut Criterion) {
+    let mut drawer = drawer();
+    let mut x = 0.0;
+
+    c.bench_function("hover over 10k circles", |b| {
+        b.iter(|| {
+            x = (x + 7.0) % CANVAS_SIZE;
+            let _ = drawer.update(circle_drawer::Message::Mouse(Mouse {
+                event: MouseEvent::Moved,
+                point: black_box(Point::new(x, CANVAS_SIZE - x)),
+            }));
+        });
+    });
+}
+
+criterion_group!(benches, hover);
+criterion_main!(benches);
@@ -0,0 +1,18 @@
+use iced::Size;
+use iced_7guis::launcher::Launcher;
+use iced_aw::ICED_AW_FONT_BYTES;
+
+/// # Errors
+///
+/// The application may error.
+pub fn main() -> iced::Result {
+    iced::application(Launcher::default, Launcher::update, Launcher::view)
+        .title("7 GUIs")
+        .font(ICED_AW_FONT_BYTES)
+        .window_size(Size {
+            width: 1000.0,
+            height: 900.0,
+        })
+        .subscription(Launcher::subscription)
+        .run()
+}
@@ -0,0 +1,123 @@
+use iced::{
+    Alignment, Element, Length, Size, Task,
+    widget::{Column, Row, button, container, operation, scrollable, text, text_input},
+    window,
+};
+use iced_7guis::cells::{COLUMNS, Cell, ROWS, Sheet};
+
+const CELL_WIDTH: f32 = 100.0;
+const CELL_HEIGHT: f32 = 30.0;
+const HEADER_WIDTH: f32 = 40.0;
+const EDITOR: &str = "cell editor";
+
+/// # Errors
+///
+/// The application may error.
+pub fn main() -> iced::Result {
+    iced::application(Cells::default, Cells::update, Cells::view)
+        .title("Cells")
+        .window(window::Settings {
+            size: Size {
+                width: 800.0,
+                height: 600.0,
+            },
+            ..Default::default()
+        })
+        .run()
+}
+
+#[derive(Default)]
+struct Cells {
+    sheet: Sheet,
+    editing: Option<Cell>,
+    input: String,
+}
+
+#[derive(Clone, Debug)]
+enum Message {
+    Edit(Cell),
+    InputChanged(String),
+    Submit,
+}
+
+impl Cells {
+    fn update(&mut self, message: Message) -> Task<Message> {
+        match message {
+            Message::Edit(cell) => {
+                self.submit();
+                self.editing = Some(cell);
+                self.input = self.sheet.content(cell).to_string();
+
+                operation::focus(EDITOR)
+            }
+            Message::InputChanged(input) => {
+                self.input = input;
+                Task::none()
+            }
+            Message::Submit => {
+                self.submit();
+                Task::none()
+            }
+        }
+    }
+
+    fn submit(&mut self) {
+        if let Some(cell) = self.editing.take() {
+            self.sheet.set(cell, &self.input);
+            self.input = String::new();
+        }
+    }
+
+    fn view(&self) -> Element<'_, Message> {
+        let mut header = Row::new().push(text("").width(HEADER_WIDTH));
+        for column in 0..COLUMNS {
+            header = header.push(
+                text(char::from(b'A' + column).to_string())
+                    .width(CELL_WIDTH)
+                    .center(),
+            );
+        }
+
+        let mut grid = Column::new().push(header);
+        for row in 0..ROWS {
+            let mut cells = Row::new()
+                .push(text(row).width(HEADER_WIDTH).center())
+                .height(CELL_HEIGHT)
+                .align_y(Alignment::Center);
+
+            for column in 0..COLUMNS {
+                let cell =
+                    Cell::new(column, row).expect("the grid only has cells inside the sheet");
+
+                let element: Element<'_, Message> = if self.editing == Some(cell) {
+                    text_input("", &self.input)
+                        .id(EDITOR)
+                        .on_input(Message::InputChanged)
+                        .on_submit(Message::Submit)
+                        .width(CELL_WIDTH)
+                        .into()
+                } else {
+                    button(text(self.sheet.value(cell).to_string()))
+                        .on_press(Message::Edit(cell))
+                        .style(button::text)
+                        .width(CELL_WIDTH)
+                        .height(CELL_HEIGHT)
+                        .into()
+                };
+
+                cells = cells.push(container(element).style(container::bordered_box));
+            }
+
+            grid = grid.push(cells);
+        }
+
+        scrollable(grid)
+            .direction(scrollable::Direction::Both {
+                vertical: scrollable::Scrollbar::default(),
+                horizontal: scrollable::Scrollbar::default(),
+            })
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .into()
+    }
+}
@@ -1,177 +1,80 @@
-use iced::{
-    Alignment, Element, Font, Size,
-    widget::{button, column, row, text_input},
-    window,
-};
-use iced_aw::{SelectionList, style};
+use std::{path::Path, process};
 
-const SPACING: u32 = 10;
+use iced::{Size, window};
+use iced_7guis::{crud::Crud, keys};
+
+const USAGE: &str = "usage: 7guis_crud [--import <file> | --export <file>]";
+
+/// Imports or exports a CSV or vCard file without opening the window. Returns whether it worked.
+fn run_command(command: &str, file: &str) -> bool {
+    let mut crud = Crud::load();
+    if let Some(error) = crud.error() {
+        eprintln!("{error}");
+    }
+
+    let path = Path::new(file);
+    match command {
+        "--import" => match crud.import(path) {
+            Ok(import) => {
+                for error in &import.errors {
+                    eprintln!("{file}: {error}");
+                }
+                println!(
+                    "Imported {} people from {file}, skipped {} lines.",
+                    import.contacts.len(),
+                    import.errors.len()
+                );
+                if let Some(error) = crud.error() {
+                    eprintln!("{error}");
+                    return false;
+                }
+                import.errors.is_empty()
+            }
+            Err(error) => {
+                eprintln!("Could not import {file}: {error}");
+                false
+            }
+        },
+        "--export" => match crud.export(path) {
+            Ok(()) => {
+                println!("Exported {} people to {file}.", crud.people().len());
+                true
+            }
+            Err(error) => {
+                eprintln!("Could not export {file}: {error}");
+                false
+            }
+        },
+        _ => {
+            eprintln!("{USAGE}");
+            false
+        }
+    }
+}
 
 /// # Errors
 ///
 /// The application may error.
 pub fn main() -> iced::Result {
-    iced::application(Crud::default, Crud::update, Crud::view)
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match args.as_slice() {
+        [] => {}
+        [command, file] => process::exit(i32::from(!run_command(command, file))),
+        _ => {
+            eprintln!("{USAGE}");
+            process::exit(2);
+        }
+    }
+
+    iced::application(Crud::load, Crud::update, keys::view_with_error(Crud::view))
+        .subscription(Crud::subscription)
         .title("CRUD")
         .window(window::Settings {
             size: Size {
                 width: 580.0,
-                height: 320.0,
+                height: 520.0,
             },
             ..Default::default()
         })
         .run()
 }
-
-#[derive(Default)]
-struct Crud {
-    filter_prefix: String,
-    selected_name: Option<usize>,
-    name: String,
-    sur_name: String,
-    names: Vec<String>,
-    display_names: Vec<String>,
-}
-
-#[derive(Clone, Debug)]
-enum Message {
-    FilterPrefixChanged(String),
-    SelectedName(usize, String),
-    NameChanged(String),
-    SurnameChanged(String),
-    CreatePressed,
-    UpdatePressed,
-    DeletePressed,
-}
-
-impl Crud {
-    fn update(&mut self, message: Message) {
-        match message {
-            Message::FilterPrefixChanged(prefix) => {
-                self.filter_prefix = prefix;
-                self.selected_name = None;
-            }
-            Message::SelectedName(index, _string) => {
-                self.selected_name = Some(index);
-            }
-            Message::NameChanged(name) => {
-                self.name = name;
-            }
-            Message::SurnameChanged(name) => {
-                self.sur_name = name;
-            }
-            Message::CreatePressed => {
-                self.names.push(format!("{}, {}", self.sur_name, self.name));
-                self.selected_name = None;
-            }
-            Message::UpdatePressed => {
-                if let Some(index) = self.selected_name {
-                    let name_chosen = &self.display_names[index];
-                    let mut j = 0;
-                    for (i, name) in self.names.iter().enumerate() {
-                        if name_chosen == name {
-                            j = i;
-                            break;
-                        }
-                    }
-                    self.names[j] = format!("{}, {}", self.sur_name, self.name);
-                }
-            }
-            Message::DeletePressed => {
-                if let Some(index) = self.selected_name {
-                    let name_chosen = self.display_names.remove(index);
-                    let mut j = 0;
-                    for (i, name) in self.names.iter().enumerate() {
-                        if &name_chosen == name {
-                            j = i;
-                            break;
-                        }
-                    }
-                    self.names.remove(j);
-                }
-                self.selected_name = None;
-            }
-        }
-
-        self.display_names = Vec::new();
-        for name in &self.names {
-            if name[..self.filter_prefix.len()] == self.filter_prefix {
-                self.display_names.push(name.into());
-            }
-        }
-    }
-
-    fn view(&self) -> Element<'_, Message> {
-        let filter_prefix = row![
-            "Filter prefix:",
-            text_input("", &self.filter_prefix)
-                .on_input(Message::FilterPrefixChanged)
-                .width(190),
-        ]
-        .spacing(SPACING)
-        .padding(10)
-        .align_y(Alignment::Start);
-
-        let selection_list = row![
-            SelectionList::new_with(
-                &self.names[..],
-                Message::SelectedName,
-                12.0,
-                5.0,
-                style::selection_list::primary,
-                self.selected_name,
-                Font::default(),
-            )
-            .width(iced::Length::Fixed(300.0))
-            .height(iced::Length::Fixed(200.0))
-        ]
-        .padding(10);
-
-        let name = row![
-            "Name:",
-            text_input("", &self.name).on_input(Message::NameChanged)
-        ]
-        .spacing(SPACING);
-
-        let surname = row![
-            "Surname:",
-            text_input("", &self.sur_name).on_input(Message::SurnameChanged),
-        ]
-        .spacing(SPACING);
-
-        let enter_name = column![name, surname].padding(10).spacing(10);
-
-        let names_box = row![selection_list, enter_name];
-
-        let create = button("Create");
-        let create = if self.sur_name.is_empty() || self.name.is_empty() {
-            create
-        } else {
-            create.on_press(Message::CreatePressed)
-        };
-
-        let update = button("Update");
-        let update =
-            if self.sur_name.is_empty() || self.name.is_empty() || self.selected_name.is_none() {
-                update
-            } else {
-                update.on_press(Message::UpdatePressed)
-            };
-
-        let delete = button("Delete");
-        let delete = if self.selected_name.is_none() {
-            delete
-        } else {
-            delete.on_press(Message::DeletePressed)
-        };
-
-        let buttons = row![create, update, delete]
-            .padding(10)
-            .spacing(10)
-            .align_y(Alignment::Start);
-
-        column![filter_prefix, names_box, buttons]
-            .align_x(Alignment::Start)
-            .into()
-    }
-}
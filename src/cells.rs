@@ -0,0 +1,447 @@
+//! The spreadsheet behind the Cells task, without any GUI.
+
+pub mod formula;
+
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+use formula::{Expr, Function, ParseError};
+
+pub const COLUMNS: u8 = 26;
+pub const ROWS: u8 = 100;
+
+/// A cell address, such as `B12`, always inside the sheet.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Cell {
+    column: u8,
+    row: u8,
+}
+
+impl Cell {
+    /// The cell at `column` and `row`, counting from 0, if it is inside the sheet.
+    #[must_use]
+    pub fn new(column: u8, row: u8) -> Option<Self> {
+        (column < COLUMNS && row < ROWS).then_some(Cell { column, row })
+    }
+
+    #[must_use]
+    pub fn column(self) -> u8 {
+        self.column
+    }
+
+    #[must_use]
+    pub fn row(self) -> u8 {
+        self.row
+    }
+
+    /// Parses an address like `A0` or `z99`.
+    #[must_use]
+    pub fn parse(string: &str) -> Option<Self> {
+        let mut chars = string.chars();
+        let column = chars.next()?.to_ascii_uppercase();
+        if !column.is_ascii_uppercase() {
+            return None;
+        }
+
+        let row = chars.as_str();
+        if row.is_empty() || !row.bytes().all(|byte| byte.is_ascii_digit()) {
+            return None;
+        }
+
+        let column = u8::try_from(column).ok()? - b'A';
+
+        Cell::new(column, row.parse().ok()?)
+    }
+}
+
+impl fmt::Display for Cell {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", char::from(b'A' + self.column), self.row)
+    }
+}
+
+/// Why a cell has no numeric value.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Error {
+    Parse(ParseError),
+    Cycle,
+    NotANumber(Cell),
+    Reference(Cell),
+    DivisionByZero,
+    RangeOutsideFunction,
+    Arity {
+        function: Function,
+        expected: usize,
+        found: usize,
+    },
+    NoArguments(Function),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Parse(error) => write!(f, "{error}"),
+            Error::Cycle => write!(f, "cycle"),
+            Error::NotANumber(cell) => write!(f, "{cell} is not a number"),
+            Error::Reference(cell) => write!(f, "{cell} has an error"),
+            Error::DivisionByZero => write!(f, "division by zero"),
+            Error::RangeOutsideFunction => write!(f, "ranges are only allowed in functions"),
+            Error::Arity {
+                function,
+                expected,
+                found,
+            } => write!(f, "{function} takes {expected} arguments, not {found}"),
+            Error::NoArguments(function) => write!(f, "{function} needs arguments"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<ParseError> for Error {
+    fn from(error: ParseError) -> Self {
+        Error::Parse(error)
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub enum Value {
+    #[default]
+    Empty,
+    Number(f64),
+    Text(String),
+    Error(Error),
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Empty => Ok(()),
+            Value::Number(number) => write!(f, "{number}"),
+            Value::Text(text) => write!(f, "{text}"),
+            Value::Error(error) => write!(f, "#{error}"),
+        }
+    }
+}
+
+/// The cells' contents and values, and which cells read which.
+#[derive(Debug, Default)]
+pub struct Sheet {
+    contents: HashMap<Cell, String>,
+    formulas: HashMap<Cell, Expr>,
+    values: HashMap<Cell, Value>,
+    dependents: HashMap<Cell, HashSet<Cell>>,
+}
+
+impl Sheet {
+    /// What the user typed into `cell`.
+    #[must_use]
+    pub fn content(&self, cell: Cell) -> &str {
+        self.contents.get(&cell).map_or("", String::as_str)
+    }
+
+    #[must_use]
+    pub fn value(&self, cell: Cell) -> &Value {
+        self.values.get(&cell).unwrap_or(&Value::Empty)
+    }
+
+    /// The cells whose formulas read `cell`.
+    #[must_use]
+    pub fn dependents(&self, cell: Cell) -> Option<&HashSet<Cell>> {
+        self.dependents.get(&cell)
+    }
+
+    /// Sets the content of `cell` and recomputes it and every cell that depends on it.
+    ///
+    /// Content starting with `=` is a formula, content that parses as a number is a number and
+    /// anything else is text. Returns the recomputed cells in the order they were evaluated.
+    pub fn set(&mut self, cell: Cell, content: &str) -> Vec<Cell> {
+        if let Some(formula) = self.formulas.remove(&cell) {
+            for dependency in formula.references() {
+                if let Some(dependents) = self.dependents.get_mut(&dependency) {
+                    dependents.remove(&cell);
+                }
+            }
+        }
+
+        self.contents.insert(cell, content.to_string());
+
+        if let Some(formula) = content.strip_prefix('=') {
+            match Expr::parse(formula) {
+                Ok(formula) => {
+                    for dependency in formula.references() {
+                        self.dependents.entry(dependency).or_default().insert(cell);
+                    }
+                    self.formulas.insert(cell, formula);
+                }
+                Err(error) => {
+                    self.values.insert(cell, Value::Error(error.into()));
+                }
+            }
+        }
+
+        self.propagate(cell)
+    }
+
+    /// Recomputes `cell` and everything that depends on it, in dependency order.
+    fn propagate(&mut self, cell: Cell) -> Vec<Cell> {
+        let mut affected = HashSet::new();
+        let mut stack = vec![cell];
+        while let Some(cell) = stack.pop() {
+            if affected.insert(cell)
+                && let Some(dependents) = self.dependents.get(&cell)
+            {
+                stack.extend(dependents.iter().copied());
+            }
+        }
+
+        let mut pending: HashMap<Cell, usize> = affected
+            .iter()
+            .map(|cell| {
+                let count = self.formulas.get(cell).map_or(0, |formula| {
+                    formula
+                        .references()
+                        .iter()
+                        .filter(|dependency| affected.contains(dependency))
+                        .count()
+                });
+                (*cell, count)
+            })
+            .collect();
+
+        let mut ready: Vec<Cell> = pending
+            .iter()
+            .filter(|(_, count)| **count == 0)
+            .map(|(cell, _)| *cell)
+            .collect();
+
+        let mut evaluated = Vec::new();
+        loop {
+            while let Some(cell) = ready.pop() {
+                pending.remove(&cell);
+                self.evaluate(cell);
+                evaluated.push(cell);
+                self.release(cell, &mut pending, &mut ready);
+            }
+
+            // Whatever is left waits on a cycle. Mark the cells on the cycle itself, then let the
+            // cells downstream of it evaluate to reference errors.
+            let cycle: Vec<Cell> = pending
+                .keys()
+                .copied()
+                .filter(|cell| self.reaches_itself(*cell, &pending))
+                .collect();
+
+            if cycle.is_empty() {
+                return evaluated;
+            }
+
+            for cell in &cycle {
+                pending.remove(cell);
+                self.values.insert(*cell, Value::Error(Error::Cycle));
+                evaluated.push(*cell);
+            }
+            for cell in cycle {
+                self.release(cell, &mut pending, &mut ready);
+            }
+        }
+    }
+
+    fn release(&self, cell: Cell, pending: &mut HashMap<Cell, usize>, ready: &mut Vec<Cell>) {
+        if let Some(dependents) = self.dependents.get(&cell) {
+            for dependent in dependents {
+                if let Some(count) = pending.get_mut(dependent) {
+                    *count -= 1;
+                    if *count == 0 {
+                        ready.push(*dependent);
+                    }
+                }
+            }
+        }
+    }
+
+    fn reaches_itself(&self, cell: Cell, pending: &HashMap<Cell, usize>) -> bool {
+        let mut visited = HashSet::new();
+        let mut stack: Vec<Cell> = self
+            .dependents
+            .get(&cell)
+            .into_iter()
+            .flatten()
+            .copied()
+            .collect();
+
+        while let Some(next) = stack.pop() {
+            if next == cell {
+                return true;
+            }
+
+            if pending.contains_key(&next)
+                && visited.insert(next)
+                && let Some(dependents) = self.dependents.get(&next)
+            {
+                stack.extend(dependents.iter().copied());
+            }
+        }
+
+        false
+    }
+
+    fn evaluate(&mut self, cell: Cell) {
+        let value = if let Some(formula) = self.formulas.get(&cell) {
+            match formula.evaluate(&|cell| self.number(cell)) {
+                Ok(number) => Value::Number(number),
+                Err(error) => Value::Error(error),
+            }
+        } else if self.content(cell).starts_with('=') {
+            // The formula failed to parse, so keep the error from `set`.
+            return;
+        } else {
+            let content = self.content(cell).trim();
+            if content.is_empty() {
+                Value::Empty
+            } else if let Ok(number) = content.parse() {
+                Value::Number(number)
+            } else {
+                Value::Text(content.to_string())
+            }
+        };
+
+        self.values.insert(cell, value);
+    }
+
+    /// The numeric value of `cell`, where empty cells count as zero.
+    ///
+    /// # Errors
+    ///
+    /// Errors if the cell holds text or an error.
+    pub fn number(&self, cell: Cell) -> Result<f64, Error> {
+        match self.value(cell) {
+            Value::Empty => Ok(0.0),
+            Value::Number(number) => Ok(*number),
+            Value::Text(_) => Err(Error::NotANumber(cell)),
+            Value::Error(_) => Err(Error::Reference(cell)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cell(name: &str) -> Cell {
+        Cell::parse(name).unwrap()
+    }
+
+    #[test]
+    fn parses_cells() {
+        assert_eq!(Cell::new(0, 0), Some(cell("A0")));
+        assert_eq!(Cell::new(25, 99), Some(cell("z99")));
+        assert_eq!(Cell::new(COLUMNS, 0), None);
+        assert_eq!(Cell::new(0, ROWS), None);
+        assert_eq!(Cell::parse("A"), None);
+        assert_eq!(Cell::parse("A100"), None);
+        assert_eq!(Cell::parse("1A"), None);
+        assert_eq!(Cell::parse("A+1"), None);
+        assert_eq!(cell("C7").to_string(), "C7");
+    }
+
+    #[test]
+    fn classifies_content() {
+        let mut sheet = Sheet::default();
+        sheet.set(cell("A0"), "1.5");
+        sheet.set(cell("A1"), "hello");
+        sheet.set(cell("A2"), "");
+
+        assert_eq!(sheet.value(cell("A0")), &Value::Number(1.5));
+        assert_eq!(sheet.value(cell("A1")), &Value::Text("hello".to_string()));
+        assert_eq!(sheet.value(cell("A2")), &Value::Empty);
+        assert_eq!(sheet.content(cell("A1")), "hello");
+    }
+
+    #[test]
+    fn propagates_changes() {
+        let mut sheet = Sheet::default();
+        sheet.set(cell("A1"), "2");
+        sheet.set(cell("B1"), "=A1*3");
+        sheet.set(cell("C1"), "=sum(A1:B1)");
+        assert_eq!(sheet.value(cell("C1")), &Value::Number(8.0));
+
+        let recomputed = sheet.set(cell("A1"), "4");
+        assert_eq!(recomputed, vec![cell("A1"), cell("B1"), cell("C1")]);
+        assert_eq!(sheet.value(cell("B1")), &Value::Number(12.0));
+        assert_eq!(sheet.value(cell("C1")), &Value::Number(16.0));
+    }
+
+    #[test]
+    fn recomputes_only_affected_cells() {
+        let mut sheet = Sheet::default();
+        sheet.set(cell("A1"), "1");
+        sheet.set(cell("A2"), "2");
+        sheet.set(cell("B1"), "=A1+1");
+        sheet.set(cell("B2"), "=A2+1");
+
+        assert_eq!(sheet.set(cell("A2"), "5"), vec![cell("A2"), cell("B2")]);
+    }
+
+    #[test]
+    fn forgets_old_dependencies() {
+        let mut sheet = Sheet::default();
+        sheet.set(cell("B1"), "=A1");
+        sheet.set(cell("B1"), "=A2");
+
+        assert_eq!(sheet.set(cell("A1"), "1"), vec![cell("A1")]);
+        assert!(sheet.dependents(cell("A1")).unwrap().is_empty());
+    }
+
+    #[test]
+    fn reports_parse_errors() {
+        let mut sheet = Sheet::default();
+        sheet.set(cell("A0"), "=1+");
+        sheet.set(cell("A1"), "=A0");
+
+        assert_eq!(
+            sheet.value(cell("A0")),
+            &Value::Error(Error::Parse(ParseError::Expected(
+                "a number, cell or function",
+                2
+            )))
+        );
+        assert_eq!(
+            sheet.value(cell("A1")),
+            &Value::Error(Error::Reference(cell("A0")))
+        );
+    }
+
+    #[test]
+    fn reports_cycles() {
+        let mut sheet = Sheet::default();
+        sheet.set(cell("A0"), "=A0");
+        assert_eq!(sheet.value(cell("A0")), &Value::Error(Error::Cycle));
+
+        sheet.set(cell("B0"), "=B1+1");
+        sheet.set(cell("B2"), "=B0");
+        sheet.set(cell("B1"), "=B0");
+        assert_eq!(sheet.value(cell("B0")), &Value::Error(Error::Cycle));
+        assert_eq!(sheet.value(cell("B1")), &Value::Error(Error::Cycle));
+        assert_eq!(
+            sheet.value(cell("B2")),
+            &Value::Error(Error::Reference(cell("B0")))
+        );
+
+        sheet.set(cell("B1"), "1");
+        assert_eq!(sheet.value(cell("B0")), &Value::Number(2.0));
+        assert_eq!(sheet.value(cell("B2")), &Value::Number(2.0));
+    }
+
+    #[test]
+    fn reports_text_references() {
+        let mut sheet = Sheet::default();
+        sheet.set(cell("A0"), "text");
+        sheet.set(cell("A1"), "=A0+1");
+
+        assert_eq!(
+            sheet.value(cell("A1")),
+            &Value::Error(Error::NotANumber(cell("A0")))
+        );
+    }
+}
@@ -0,0 +1,645 @@
+//! Parsing and evaluation of cell formulas.
+
+use std::collections::HashSet;
+use std::fmt;
+
+use super::{Cell, Error};
+
+/// How deeply formulas may nest, so that parsing, evaluating and dropping them can't overflow the
+/// stack.
+pub const MAX_DEPTH: usize = 256;
+
+/// A parsed formula.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Expr {
+    Number(f64),
+    Cell(Cell),
+    Range(Cell, Cell),
+    Negate(Box<Expr>),
+    Binary(Operator, Box<Expr>, Box<Expr>),
+    Call(Function, Vec<Expr>),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Operator {
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Function {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Mod,
+    Sum,
+    Prod,
+    Avg,
+    Min,
+    Max,
+}
+
+impl Function {
+    /// Looks up a function by its case-insensitive name.
+    #[must_use]
+    pub fn parse(name: &str) -> Option<Self> {
+        Some(match name.to_ascii_lowercase().as_str() {
+            "add" => Function::Add,
+            "sub" => Function::Sub,
+            "mul" => Function::Mul,
+            "div" => Function::Div,
+            "mod" => Function::Mod,
+            "sum" => Function::Sum,
+            "prod" => Function::Prod,
+            "avg" => Function::Avg,
+            "min" => Function::Min,
+            "max" => Function::Max,
+            _ => return None,
+        })
+    }
+
+    /// # Errors
+    ///
+    /// Errors if the wrong number of arguments is given or on division by zero.
+    pub fn apply(self, arguments: &[f64]) -> Result<f64, Error> {
+        let binary = |operation: fn(f64, f64) -> f64| match arguments {
+            [a, b] => Ok(operation(*a, *b)),
+            _ => Err(Error::Arity {
+                function: self,
+                expected: 2,
+                found: arguments.len(),
+            }),
+        };
+
+        match self {
+            Function::Add => binary(|a, b| a + b),
+            Function::Sub => binary(|a, b| a - b),
+            Function::Mul => binary(|a, b| a * b),
+            Function::Div => match arguments {
+                [_, b] if *b == 0.0 => Err(Error::DivisionByZero),
+                _ => binary(|a, b| a / b),
+            },
+            Function::Mod => match arguments {
+                [_, b] if *b == 0.0 => Err(Error::DivisionByZero),
+                _ => binary(|a, b| a % b),
+            },
+            Function::Sum => Ok(arguments.iter().sum()),
+            Function::Prod => Ok(arguments.iter().product()),
+            Function::Avg => {
+                if arguments.is_empty() {
+                    Err(Error::NoArguments(self))
+                } else {
+                    let (sum, count) = arguments.iter().fold((0.0, 0.0), |(sum, count), number| {
+                        (sum + number, count + 1.0)
+                    });
+                    Ok(sum / count)
+                }
+            }
+            Function::Min => arguments
+                .iter()
+                .copied()
+                .reduce(f64::min)
+                .ok_or(Error::NoArguments(self)),
+            Function::Max => arguments
+                .iter()
+                .copied()
+                .reduce(f64::max)
+                .ok_or(Error::NoArguments(self)),
+        }
+    }
+}
+
+impl fmt::Display for Function {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Function::Add => "add",
+                Function::Sub => "sub",
+                Function::Mul => "mul",
+                Function::Div => "div",
+                Function::Mod => "mod",
+                Function::Sum => "sum",
+                Function::Prod => "prod",
+                Function::Avg => "avg",
+                Function::Min => "min",
+                Function::Max => "max",
+            }
+        )
+    }
+}
+
+/// Why a formula failed to parse. Positions are byte offsets into the formula.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ParseError {
+    UnexpectedCharacter(char, usize),
+    InvalidNumber(String, usize),
+    InvalidCell(String, usize),
+    UnknownFunction(String, usize),
+    Expected(&'static str, usize),
+    TrailingInput(usize),
+    TooDeep(usize),
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::UnexpectedCharacter(char, position) => {
+                write!(f, "unexpected character {char} at {position}")
+            }
+            ParseError::InvalidNumber(number, position) => {
+                write!(f, "invalid number {number} at {position}")
+            }
+            ParseError::InvalidCell(cell, position) => {
+                write!(f, "invalid cell {cell} at {position}")
+            }
+            ParseError::UnknownFunction(name, position) => {
+                write!(f, "unknown function {name} at {position}")
+            }
+            ParseError::Expected(expected, position) => {
+                write!(f, "expected {expected} at {position}")
+            }
+            ParseError::TrailingInput(position) => {
+                write!(f, "unexpected input after formula at {position}")
+            }
+            ParseError::TooDeep(position) => {
+                write!(f, "formula nested too deeply at {position}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+impl Expr {
+    /// Parses a formula, without the leading `=`.
+    ///
+    /// # Errors
+    ///
+    /// Errors if the formula is not valid.
+    pub fn parse(string: &str) -> Result<Self, ParseError> {
+        let mut parser = Parser {
+            tokens: tokenize(string)?,
+            position: 0,
+            end: string.len(),
+            depth: 0,
+        };
+
+        let expr = parser.expression()?;
+        if let Some((_, position)) = parser.tokens.get(parser.position) {
+            return Err(ParseError::TrailingInput(*position));
+        }
+
+        Ok(expr)
+    }
+
+    /// Returns every cell the formula reads, with ranges expanded.
+    #[must_use]
+    pub fn references(&self) -> HashSet<Cell> {
+        let mut cells = HashSet::new();
+        self.collect_references(&mut cells);
+        cells
+    }
+
+    fn collect_references(&self, cells: &mut HashSet<Cell>) {
+        match self {
+            Expr::Number(_) => {}
+            Expr::Cell(cell) => {
+                cells.insert(*cell);
+            }
+            Expr::Range(from, to) => cells.extend(range(*from, *to)),
+            Expr::Negate(expr) => expr.collect_references(cells),
+            Expr::Binary(_, left, right) => {
+                left.collect_references(cells);
+                right.collect_references(cells);
+            }
+            Expr::Call(_, arguments) => {
+                for argument in arguments {
+                    argument.collect_references(cells);
+                }
+            }
+        }
+    }
+
+    /// Evaluates the formula, looking up referenced cells with `number`.
+    ///
+    /// # Errors
+    ///
+    /// Errors if a referenced cell is not a number or the arithmetic fails.
+    pub fn evaluate(&self, number: &impl Fn(Cell) -> Result<f64, Error>) -> Result<f64, Error> {
+        match self {
+            Expr::Number(value) => Ok(*value),
+            Expr::Cell(cell) => number(*cell),
+            Expr::Range(..) => Err(Error::RangeOutsideFunction),
+            Expr::Negate(expr) => Ok(-expr.evaluate(number)?),
+            Expr::Binary(operator, left, right) => {
+                let left = left.evaluate(number)?;
+                let right = right.evaluate(number)?;
+
+                match operator {
+                    Operator::Add => Ok(left + right),
+                    Operator::Subtract => Ok(left - right),
+                    Operator::Multiply => Ok(left * right),
+                    Operator::Divide if right == 0.0 => Err(Error::DivisionByZero),
+                    Operator::Divide => Ok(left / right),
+                }
+            }
+            Expr::Call(function, arguments) => {
+                let mut numbers = Vec::new();
+                for argument in arguments {
+                    if let Expr::Range(from, to) = argument {
+                        for cell in range(*from, *to) {
+                            numbers.push(number(cell)?);
+                        }
+                    } else {
+                        numbers.push(argument.evaluate(number)?);
+                    }
+                }
+
+                function.apply(&numbers)
+            }
+        }
+    }
+}
+
+/// Every cell in the rectangle spanned by `from` and `to`, column by column.
+pub fn range(from: Cell, to: Cell) -> impl Iterator<Item = Cell> {
+    let columns = from.column.min(to.column)..=from.column.max(to.column);
+    let rows = from.row.min(to.row)..=from.row.max(to.row);
+
+    // Both corners are inside the sheet, so every cell between them is too.
+    columns.flat_map(move |column| rows.clone().map(move |row| Cell { column, row }))
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Number(f64),
+    Identifier(String),
+    Plus,
+    Minus,
+    Star,
+    Slash,
+    Colon,
+    Comma,
+    LeftParen,
+    RightParen,
+}
+
+fn tokenize(string: &str) -> Result<Vec<(Token, usize)>, ParseError> {
+    let mut tokens = Vec::new();
+    let mut chars = string.char_indices().peekable();
+
+    while let Some((start, char)) = chars.next() {
+        let token = match char {
+            ' ' | '\t' => continue,
+            '+' => Token::Plus,
+            '-' => Token::Minus,
+            '*' => Token::Star,
+            '/' => Token::Slash,
+            ':' => Token::Colon,
+            ',' => Token::Comma,
+            '(' => Token::LeftParen,
+            ')' => Token::RightParen,
+            '0'..='9' | '.' => {
+                let mut end = start + char.len_utf8();
+                while let Some((index, char)) = chars.peek()
+                    && (char.is_ascii_digit() || *char == '.')
+                {
+                    end = index + char.len_utf8();
+                    chars.next();
+                }
+
+                let number = &string[start..end];
+                Token::Number(
+                    number
+                        .parse()
+                        .map_err(|_| ParseError::InvalidNumber(number.to_string(), start))?,
+                )
+            }
+            char if char.is_ascii_alphabetic() => {
+                let mut end = start + char.len_utf8();
+                while let Some((index, char)) = chars.peek()
+                    && char.is_ascii_alphanumeric()
+                {
+                    end = index + char.len_utf8();
+                    chars.next();
+                }
+
+                Token::Identifier(string[start..end].to_string())
+            }
+            char => return Err(ParseError::UnexpectedCharacter(char, start)),
+        };
+
+        tokens.push((token, start));
+    }
+
+    Ok(tokens)
+}
+
+/// A recursive descent parser for the grammar:
+///
+/// ```text
+/// expression = term (("+" | "-") term)*
+/// term       = factor (("*" | "/") factor)*
+/// factor     = "-" factor | number | cell (":" cell)? | name "(" arguments ")" | "(" expression ")"
+/// ```
+///
+/// Each operator, negation and parenthesis nests the formula a level deeper, up to [`MAX_DEPTH`].
+struct Parser {
+    tokens: Vec<(Token, usize)>,
+    position: usize,
+    end: usize,
+    depth: usize,
+}
+
+impl Parser {
+    fn next(&mut self) -> (Option<Token>, usize) {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+
+        match token {
+            Some((token, position)) => (Some(token), position),
+            None => (None, self.end),
+        }
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position).map(|(token, _)| token)
+    }
+
+    /// Goes a level deeper for the token at `position`.
+    fn deepen(&mut self, position: usize) -> Result<(), ParseError> {
+        if self.depth == MAX_DEPTH {
+            return Err(ParseError::TooDeep(position));
+        }
+        self.depth += 1;
+
+        Ok(())
+    }
+
+    /// Parses with `parse` a level deeper for the token at `position`.
+    fn nested(
+        &mut self,
+        position: usize,
+        parse: impl FnOnce(&mut Self) -> Result<Expr, ParseError>,
+    ) -> Result<Expr, ParseError> {
+        self.deepen(position)?;
+        let expr = parse(self)?;
+        self.depth -= 1;
+
+        Ok(expr)
+    }
+
+    fn expression(&mut self) -> Result<Expr, ParseError> {
+        let depth = self.depth;
+        let mut left = self.term()?;
+
+        loop {
+            let (operator, position) = match self.tokens.get(self.position) {
+                Some((Token::Plus, position)) => (Operator::Add, *position),
+                Some((Token::Minus, position)) => (Operator::Subtract, *position),
+                _ => {
+                    self.depth = depth;
+                    return Ok(left);
+                }
+            };
+            self.position += 1;
+            // Each operator puts the ones before it a level deeper.
+            self.deepen(position)?;
+
+            left = Expr::Binary(operator, Box::new(left), Box::new(self.term()?));
+        }
+    }
+
+    fn term(&mut self) -> Result<Expr, ParseError> {
+        let depth = self.depth;
+        let mut left = self.factor()?;
+
+        loop {
+            let (operator, position) = match self.tokens.get(self.position) {
+                Some((Token::Star, position)) => (Operator::Multiply, *position),
+                Some((Token::Slash, position)) => (Operator::Divide, *position),
+                _ => {
+                    self.depth = depth;
+                    return Ok(left);
+                }
+            };
+            self.position += 1;
+            self.deepen(position)?;
+
+            left = Expr::Binary(operator, Box::new(left), Box::new(self.factor()?));
+        }
+    }
+
+    fn factor(&mut self) -> Result<Expr, ParseError> {
+        match self.next() {
+            (Some(Token::Minus), position) => self.nested(position, |parser| {
+                Ok(Expr::Negate(Box::new(parser.factor()?)))
+            }),
+            (Some(Token::Number(number)), _) => Ok(Expr::Number(number)),
+            (Some(Token::LeftParen), position) => self.nested(position, |parser| {
+                let expr = parser.expression()?;
+                match parser.next() {
+                    (Some(Token::RightParen), _) => Ok(expr),
+                    (_, position) => Err(ParseError::Expected(")", position)),
+                }
+            }),
+            (Some(Token::Identifier(name)), position) => {
+                if self.peek() == Some(&Token::LeftParen) {
+                    self.position += 1;
+                    let function = Function::parse(&name)
+                        .ok_or(ParseError::UnknownFunction(name, position))?;
+
+                    self.nested(position, |parser| {
+                        Ok(Expr::Call(function, parser.arguments()?))
+                    })
+                } else {
+                    self.cell_or_range(&name, position)
+                }
+            }
+            (_, position) => Err(ParseError::Expected("a number, cell or function", position)),
+        }
+    }
+
+    fn arguments(&mut self) -> Result<Vec<Expr>, ParseError> {
+        let mut arguments = Vec::new();
+        if self.peek() == Some(&Token::RightParen) {
+            self.position += 1;
+            return Ok(arguments);
+        }
+
+        loop {
+            arguments.push(self.expression()?);
+            match self.next() {
+                (Some(Token::Comma), _) => {}
+                (Some(Token::RightParen), _) => return Ok(arguments),
+                (_, position) => return Err(ParseError::Expected(", or )", position)),
+            }
+        }
+    }
+
+    fn cell_or_range(&mut self, name: &str, position: usize) -> Result<Expr, ParseError> {
+        let from = Cell::parse(name).ok_or(ParseError::InvalidCell(name.to_string(), position))?;
+
+        if self.peek() != Some(&Token::Colon) {
+            return Ok(Expr::Cell(from));
+        }
+        self.position += 1;
+
+        match self.next() {
+            (Some(Token::Identifier(name)), position) => {
+                let to = Cell::parse(&name).ok_or(ParseError::InvalidCell(name, position))?;
+                Ok(Expr::Range(from, to))
+            }
+            (_, position) => Err(ParseError::Expected("a cell", position)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cell(name: &str) -> Cell {
+        Cell::parse(name).unwrap()
+    }
+
+    fn evaluate(formula: &str) -> Result<f64, Error> {
+        Expr::parse(formula)
+            .unwrap()
+            .evaluate(&|cell| Ok(f64::from(cell.row())))
+    }
+
+    #[test]
+    fn parses_precedence() {
+        assert_eq!(
+            Expr::parse("A1+B2*3").unwrap(),
+            Expr::Binary(
+                Operator::Add,
+                Box::new(Expr::Cell(cell("A1"))),
+                Box::new(Expr::Binary(
+                    Operator::Multiply,
+                    Box::new(Expr::Cell(cell("B2"))),
+                    Box::new(Expr::Number(3.0)),
+                )),
+            )
+        );
+    }
+
+    #[test]
+    fn parses_ranges_in_calls() {
+        assert_eq!(
+            Expr::parse("sum(A1:B5)").unwrap(),
+            Expr::Call(Function::Sum, vec![Expr::Range(cell("A1"), cell("B5"))])
+        );
+    }
+
+    #[test]
+    fn reports_parse_errors() {
+        assert_eq!(
+            Expr::parse("1 $ 2"),
+            Err(ParseError::UnexpectedCharacter('$', 2))
+        );
+        assert_eq!(
+            Expr::parse("1.2.3"),
+            Err(ParseError::InvalidNumber("1.2.3".to_string(), 0))
+        );
+        assert_eq!(
+            Expr::parse("A100"),
+            Err(ParseError::InvalidCell("A100".to_string(), 0))
+        );
+        assert_eq!(
+            Expr::parse("foo(1)"),
+            Err(ParseError::UnknownFunction("foo".to_string(), 0))
+        );
+        assert_eq!(Expr::parse("(1"), Err(ParseError::Expected(")", 2)));
+        assert_eq!(Expr::parse("A1:"), Err(ParseError::Expected("a cell", 3)));
+        assert_eq!(Expr::parse("1 2"), Err(ParseError::TrailingInput(2)));
+        assert_eq!(
+            Expr::parse(""),
+            Err(ParseError::Expected("a number, cell or function", 0))
+        );
+    }
+
+    #[test]
+    fn limits_nesting() {
+        let nested = |prefix: &str, count: usize, suffix: &str| {
+            Expr::parse(&format!(
+                "{}1{}",
+                prefix.repeat(count),
+                suffix.repeat(count)
+            ))
+        };
+
+        assert!(nested("-", MAX_DEPTH, "").is_ok());
+        assert!(nested("(", MAX_DEPTH, ")").is_ok());
+        assert!(nested("sum(", MAX_DEPTH, ")").is_ok());
+        assert!(nested("", MAX_DEPTH, "+1").is_ok());
+
+        assert_eq!(
+            nested("-", 100_000, ""),
+            Err(ParseError::TooDeep(MAX_DEPTH))
+        );
+        assert_eq!(
+            nested("(", 100_000, ")"),
+            Err(ParseError::TooDeep(MAX_DEPTH))
+        );
+        assert_eq!(
+            nested("", 100_000, "*1"),
+            Err(ParseError::TooDeep(2 * MAX_DEPTH + 1))
+        );
+    }
+
+    #[test]
+    fn evaluates_operators() {
+        assert_eq!(evaluate("1+2"), Ok(3.0));
+        assert_eq!(evaluate("1-2"), Ok(-1.0));
+        assert_eq!(evaluate("2*3"), Ok(6.0));
+        assert_eq!(evaluate("3/2"), Ok(1.5));
+        assert_eq!(evaluate("-(1+2)"), Ok(-3.0));
+        assert_eq!(evaluate("2*(3+4)"), Ok(14.0));
+        assert_eq!(evaluate("10-4-3"), Ok(3.0));
+        assert_eq!(evaluate("1/0"), Err(Error::DivisionByZero));
+    }
+
+    #[test]
+    fn evaluates_references() {
+        assert_eq!(evaluate("A2+B3"), Ok(5.0));
+        assert_eq!(evaluate("A1:A2"), Err(Error::RangeOutsideFunction));
+    }
+
+    #[test]
+    fn evaluates_functions() {
+        assert_eq!(evaluate("add(1, 2)"), Ok(3.0));
+        assert_eq!(evaluate("sub(1, 2)"), Ok(-1.0));
+        assert_eq!(evaluate("mul(2, 3)"), Ok(6.0));
+        assert_eq!(evaluate("div(3, 2)"), Ok(1.5));
+        assert_eq!(evaluate("mod(7, 3)"), Ok(1.0));
+        assert_eq!(evaluate("SUM(A1:B3)"), Ok(12.0));
+        assert_eq!(evaluate("prod(A1:A3, 2)"), Ok(12.0));
+        assert_eq!(evaluate("avg(A1:A3)"), Ok(2.0));
+        assert_eq!(evaluate("min(A1:A3, 0)"), Ok(0.0));
+        assert_eq!(evaluate("max(A1:A3)"), Ok(3.0));
+    }
+
+    #[test]
+    fn reports_function_errors() {
+        assert_eq!(evaluate("div(1, 0)"), Err(Error::DivisionByZero));
+        assert_eq!(evaluate("mod(1, 0)"), Err(Error::DivisionByZero));
+        assert_eq!(
+            evaluate("add(1)"),
+            Err(Error::Arity {
+                function: Function::Add,
+                expected: 2,
+                found: 1,
+            })
+        );
+        assert_eq!(evaluate("avg()"), Err(Error::NoArguments(Function::Avg)));
+        assert_eq!(evaluate("sum()"), Ok(0.0));
+        assert_eq!(evaluate("prod()"), Ok(1.0));
+    }
+}
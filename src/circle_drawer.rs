@@ -0,0 +1,1530 @@
+//! Circle Drawer: click to draw circles, drag to move and right click to resize them, with undo
+//! and redo. Several shapes can be selected to restyle, copy or delete them together.
+
+pub mod camera;
+pub mod document;
+mod grid;
+pub mod png;
+pub mod style;
+pub mod svg;
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    ops::RangeInclusive,
+    path::Path as FilePath,
+};
+
+use camera::Camera;
+use grid::Grid;
+use iced::{
+    Element, Point, Radians, Rectangle, Size, Subscription, Task, Theme, Vector, clipboard,
+    keyboard::{self, Modifiers},
+    mouse::{Interaction, ScrollDelta},
+    widget::{
+        Column, Stack, button,
+        canvas::{Cache, Canvas, Frame, Geometry, Path, Program, Stroke, path::arc::Elliptical},
+        center, container, pick_list, row, slider, text, text_input,
+    },
+};
+use iced_aw::helpers::card;
+use style::{HOVER, NamedColor, OUTLINE_WIDTH, PALETTE, SELECTION, STROKE_WIDTHS, Shape, Style};
+use sweeten::mouse_area;
+
+use crate::keys;
+
+/// The width and height of the canvas.
+pub const CANVAS_SIZE: f32 = 800.0;
+
+/// The radii the slider can set.
+pub const RADIUS: RangeInclusive<f32> = 10.0..=100.0;
+
+/// The drawer's actions and their default shortcuts.
+pub const KEYS: &keys::Defaults = &[
+    ("undo", "Ctrl+Z"),
+    ("redo", "Ctrl+Shift+Z"),
+    ("copy", "Ctrl+C"),
+    ("paste", "Ctrl+V"),
+    ("delete", "Delete"),
+    ("pan", "Space"),
+];
+
+/// How far a touchpad scrolls for one wheel notch of zoom.
+const PIXELS_PER_NOTCH: f32 = 50.0;
+
+/// How far pasted shapes are moved from the copied ones, so both can be seen.
+const PASTE_OFFSET: Vector = Vector::new(20.0, 20.0);
+
+/// How far, in screen pixels, the cursor may move between press and release for a click.
+const CLICK_SLOP: f32 = 3.0;
+
+/// The circle drawer. It needs [`iced_aw::ICED_AW_FONT_BYTES`] loaded to draw its dialog.
+#[derive(Debug, Default)]
+pub struct CircleDrawer {
+    /// The circles by ID. IDs only grow, so this is also the order they are drawn in.
+    circles: BTreeMap<u64, Circle>,
+    next_id: u64,
+    grid: Grid,
+    hovered: Option<u64>,
+    selected: BTreeSet<u64>,
+    /// The shape and style of new shapes, and of the selected one.
+    shape: Shape,
+    style: Style,
+    history: Vec<Command>,
+    undone: Vec<Command>,
+    display_size: Option<Resizing>,
+    dragging: Option<Dragging>,
+    camera: Camera,
+    /// The last cursor position on the canvas, which the wheel zooms around.
+    cursor: Point,
+    /// Whether the pan key is held, so dragging with the left button pans.
+    pan_held: bool,
+    /// The held modifiers, where shift adds to the selection.
+    modifiers: Modifiers,
+    /// The rubber band being dragged out from empty canvas.
+    banding: Option<Band>,
+    /// The cursor position during a pan, on the canvas.
+    panning: Option<Point>,
+    file: String,
+    status: Option<String>,
+    error: Option<String>,
+    /// The drawn shapes, cleared whenever they, the pan or the zoom change. The outlines and
+    /// the rubber band are drawn over them on every frame.
+    cache: Cache,
+    /// How often the drawn shapes were cleared, so tests can tell when they are kept.
+    #[cfg(test)]
+    redraws: u64,
+}
+
+/// An edit that can be undone and redone.
+#[derive(Clone, Debug)]
+enum Command {
+    Create(u64, Circle),
+    Delete(u64, Circle),
+    Resize {
+        id: u64,
+        from: f32,
+        to: f32,
+    },
+    Move {
+        id: u64,
+        from: Point,
+        to: Point,
+    },
+    Restyle {
+        id: u64,
+        from: (Shape, Style),
+        to: (Shape, Style),
+    },
+    /// Edits made together, such as a paste, undone and redone as one.
+    Batch(Vec<Command>),
+}
+
+/// The circle shown in the radius dialog, and its radius when the dialog opened or the slider
+/// was last released.
+#[derive(Clone, Debug)]
+struct Resizing {
+    id: u64,
+    from: f32,
+}
+
+/// The corners of a rubber band, in world coordinates, from where it was started.
+#[derive(Clone, Copy, Debug)]
+struct Band {
+    from: Point,
+    to: Point,
+}
+
+/// The circle being dragged, where it was when the drag started, and where it was grabbed
+/// relative to its center.
+#[derive(Clone, Debug)]
+struct Dragging {
+    id: u64,
+    from: Point,
+    grab: Vector,
+}
+
+impl CircleDrawer {
+    /// A drawing of `circles`, with no history.
+    #[must_use]
+    pub fn with_circles(circles: Vec<Circle>) -> Self {
+        let mut drawer = CircleDrawer::default();
+        for circle in circles {
+            drawer.add(drawer.next_id, circle);
+            drawer.next_id += 1;
+        }
+        drawer
+    }
+
+    #[must_use]
+    pub fn circles(&self) -> Vec<Circle> {
+        self.circles.values().cloned().collect()
+    }
+
+    /// The selected shapes, in the order they are drawn.
+    #[must_use]
+    pub fn selected(&self) -> Vec<Circle> {
+        self.selected
+            .iter()
+            .filter_map(|id| self.circles.get(id).cloned())
+            .collect()
+    }
+
+    /// Replaces the drawing with the document at `path`, forgetting the history.
+    ///
+    /// # Errors
+    ///
+    /// Errors if the file can't be read or is not a valid document. The drawing is kept then.
+    pub fn open(&mut self, path: &FilePath) -> anyhow::Result<()> {
+        let drawer = CircleDrawer::with_circles(document::read(path)?);
+
+        self.circles = drawer.circles;
+        self.next_id = drawer.next_id;
+        self.grid = drawer.grid;
+        self.hovered = None;
+        self.selected.clear();
+        self.banding = None;
+        self.history.clear();
+        self.undone.clear();
+        self.display_size = None;
+        self.dragging = None;
+        self.camera = Camera::default();
+        self.redraw();
+        Ok(())
+    }
+
+    /// Writes the drawing to `path` as a versioned JSON document.
+    ///
+    /// # Errors
+    ///
+    /// Errors if the file can't be written.
+    pub fn save(&self, path: &FilePath) -> anyhow::Result<()> {
+        document::write(path, &self.circles())
+    }
+
+    /// The drawing as an SVG document, as the canvas draws it.
+    #[must_use]
+    pub fn to_svg(&self) -> String {
+        svg::to_svg(&self.circles())
+    }
+
+    /// Writes the drawing to `path` as an SVG document.
+    ///
+    /// # Errors
+    ///
+    /// Errors if the file can't be written.
+    pub fn export_svg(&self, path: &FilePath) -> anyhow::Result<()> {
+        std::fs::write(path, self.to_svg())?;
+        Ok(())
+    }
+
+    /// Renders the drawing to `path` as a PNG image, on the CPU.
+    ///
+    /// # Errors
+    ///
+    /// Errors if the image can't be encoded or the file can't be written.
+    pub fn export_png(&self, path: &FilePath) -> anyhow::Result<()> {
+        std::fs::write(path, png::to_png(&self.circles())?)?;
+        Ok(())
+    }
+
+    pub fn update(&mut self, message: Message) -> Task<Message> {
+        match message {
+            Message::Copy => return self.copy(),
+            Message::Delete => self.delete(),
+            Message::Paste => return clipboard::read().map(Message::Pasted),
+            Message::Pasted(Some(json)) => self.paste(&json),
+            Message::Pasted(None) => {}
+            Message::Mouse(mouse) => self.mouse(mouse),
+            Message::ModifiersChanged(modifiers) => self.modifiers = modifiers,
+            Message::CloseSize => {
+                self.commit_resize();
+                self.display_size = None;
+            }
+            Message::FitAll => {
+                self.camera = Camera::fit(&self.circles());
+                self.redraw();
+            }
+            Message::Redo => self.redo(),
+            Message::Scrolled(delta) => {
+                let notches = match delta {
+                    ScrollDelta::Lines { y, .. } => y,
+                    ScrollDelta::Pixels { y, .. } => y / PIXELS_PER_NOTCH,
+                };
+                self.camera.zoom_at(self.cursor, notches);
+                self.redraw();
+            }
+            Message::PanHeld(held) => self.pan_held = held,
+            Message::SizeChange(radius) => {
+                if let Some(resizing) = &self.display_size {
+                    self.set_radius(resizing.id, radius);
+                }
+            }
+            Message::SizeReleased => self.commit_resize(),
+            Message::FileChanged(file) => self.file = file,
+            Message::OpenPressed
+            | Message::SavePressed
+            | Message::ExportSvgPressed
+            | Message::ExportPngPressed => self.file_action(&message),
+            Message::ShapeSelected(_)
+            | Message::FillSelected(_)
+            | Message::StrokeSelected(_)
+            | Message::StrokeWidthSelected(_) => self.style_action(&message),
+            Message::Undo => self.undo(),
+        }
+
+        Task::none()
+    }
+
+    /// Applies a shape or style attribute picked in the style bar, leaving the others as they are.
+    fn style_action(&mut self, message: &Message) {
+        self.restyle(|(mut shape, mut style)| {
+            match *message {
+                Message::ShapeSelected(picked) => shape = picked,
+                Message::FillSelected(fill) => style.fill = fill.color,
+                Message::StrokeSelected(stroke) => style.stroke = stroke.color,
+                Message::StrokeWidthSelected(width) => style.stroke_width = width.0,
+                _ => {}
+            }
+            (shape, style)
+        });
+    }
+
+    /// Writes the selected shapes to the system clipboard as a JSON document.
+    fn copy(&mut self) -> Task<Message> {
+        let circles = self.selected();
+        if circles.is_empty() {
+            return Task::none();
+        }
+
+        match document::to_json(&circles) {
+            Ok(json) => {
+                self.show(Ok(format!("Copied {} shapes.", circles.len())), "copy");
+                clipboard::write(json)
+            }
+            Err(error) => {
+                self.show(Err(error), "copy");
+                Task::none()
+            }
+        }
+    }
+
+    /// Adds the shapes in a copied JSON document, offset from the copies and selected, as one
+    /// step.
+    fn paste(&mut self, json: &str) {
+        self.commit_pending();
+        let circles = match document::from_json(json) {
+            Ok(circles) => circles,
+            Err(error) => return self.show(Err(error), "paste"),
+        };
+
+        let (mut ids, mut commands) = (BTreeSet::new(), Vec::new());
+        for mut circle in circles {
+            circle.center += PASTE_OFFSET;
+            let id = self.next_id;
+            self.next_id += 1;
+            self.add(id, circle.clone());
+            ids.insert(id);
+            commands.push(Command::Create(id, circle));
+        }
+
+        self.select(ids, None);
+        self.push_all(commands);
+    }
+
+    /// Removes the selected shapes, as one step.
+    fn delete(&mut self) {
+        self.commit_pending();
+        let commands: Vec<Command> = self
+            .selected
+            .iter()
+            .filter_map(|id| Some(Command::Delete(*id, self.circles.get(id)?.clone())))
+            .collect();
+
+        for command in &commands {
+            self.apply(command);
+        }
+        self.push_all(commands);
+    }
+
+    /// Opens, saves or exports the file named in the toolbar.
+    fn file_action(&mut self, message: &Message) {
+        match message {
+            Message::OpenPressed => {
+                let file = self.file.clone();
+                let result = self.open(FilePath::new(&file));
+                self.show(
+                    result.map(|()| format!("Opened {file}.")),
+                    &format!("open {file}"),
+                );
+            }
+            Message::ExportSvgPressed => {
+                let path = FilePath::new(&self.file).with_extension("svg");
+                let result = self
+                    .export_svg(&path)
+                    .map(|()| format!("Exported {}.", path.display()));
+                self.show(result, &format!("export {}", path.display()));
+            }
+            Message::ExportPngPressed => {
+                let path = FilePath::new(&self.file).with_extension("png");
+                let result = self
+                    .export_png(&path)
+                    .map(|()| format!("Exported {}.", path.display()));
+                self.show(result, &format!("export {}", path.display()));
+            }
+            Message::SavePressed => {
+                let result = self.save(FilePath::new(&self.file));
+                self.show(
+                    result.map(|()| format!("Saved {}.", self.file)),
+                    &format!("save {}", self.file),
+                );
+            }
+            _ => {}
+        }
+    }
+
+    fn undo(&mut self) {
+        self.commit_pending();
+        self.display_size = None;
+
+        if let Some(command) = self.history.pop() {
+            self.revert(&command);
+            self.undone.push(command);
+        }
+    }
+
+    fn redo(&mut self) {
+        self.commit_pending();
+        self.display_size = None;
+
+        if let Some(command) = self.undone.pop() {
+            self.apply(&command);
+            self.history.push(command);
+        }
+    }
+
+    /// Does `command` again.
+    fn apply(&mut self, command: &Command) {
+        match command {
+            Command::Create(id, circle) => self.add(*id, circle.clone()),
+            Command::Delete(id, _) => self.remove(*id),
+            Command::Resize { id, to, .. } => self.set_radius(*id, *to),
+            Command::Move { id, to, .. } => self.set_center(*id, *to),
+            Command::Restyle { id, to, .. } => self.set_look(*id, *to),
+            Command::Batch(commands) => {
+                for command in commands {
+                    self.apply(command);
+                }
+            }
+        }
+    }
+
+    /// Undoes `command`.
+    fn revert(&mut self, command: &Command) {
+        match command {
+            Command::Create(id, _) => self.remove(*id),
+            Command::Delete(id, circle) => self.add(*id, circle.clone()),
+            Command::Resize { id, from, .. } => self.set_radius(*id, *from),
+            Command::Move { id, from, .. } => self.set_center(*id, *from),
+            Command::Restyle { id, from, .. } => self.set_look(*id, *from),
+            Command::Batch(commands) => {
+                for command in commands.iter().rev() {
+                    self.revert(command);
+                }
+            }
+        }
+    }
+
+    /// Clears the drawn shapes, so the canvas draws them again.
+    fn redraw(&mut self) {
+        self.cache.clear();
+        #[cfg(test)]
+        {
+            self.redraws += 1;
+        }
+    }
+
+    /// Adds `circle`, indexing it for hit-testing.
+    fn add(&mut self, id: u64, circle: Circle) {
+        self.grid.set(id, &circle);
+        self.circles.insert(id, circle);
+        self.redraw();
+    }
+
+    fn remove(&mut self, id: u64) {
+        self.grid.remove(id);
+        self.circles.remove(&id);
+        if self.hovered == Some(id) {
+            self.hovered = None;
+        }
+        self.selected.remove(&id);
+        self.redraw();
+    }
+
+    /// Changes circle `id` and re-indexes it.
+    fn edit(&mut self, id: u64, edit: impl FnOnce(&mut Circle)) {
+        if let Some(circle) = self.circles.get_mut(&id) {
+            edit(circle);
+            self.grid.set(id, circle);
+            self.redraw();
+        }
+    }
+
+    fn set_radius(&mut self, id: u64, radius: f32) {
+        self.edit(id, |circle| circle.radius = radius);
+    }
+
+    fn set_center(&mut self, id: u64, center: Point) {
+        self.edit(id, |circle| circle.center = center);
+    }
+
+    fn set_look(&mut self, id: u64, (shape, style): (Shape, Style)) {
+        self.edit(id, |circle| {
+            circle.shape = shape;
+            circle.style = style;
+        });
+        if self.selected.first() == Some(&id) {
+            (self.shape, self.style) = (shape, style);
+        }
+    }
+
+    /// Changes the look of new shapes with `pick`, and of each selected shape, as one step.
+    fn restyle(&mut self, pick: impl Fn((Shape, Style)) -> (Shape, Style)) {
+        self.commit_pending();
+        let commands: Vec<Command> = self
+            .selected
+            .iter()
+            .filter_map(|id| {
+                let circle = self.circles.get(id)?;
+                let from = (circle.shape, circle.style);
+                let to = pick(from);
+                (from != to).then_some(Command::Restyle { id: *id, from, to })
+            })
+            .collect();
+
+        for command in &commands {
+            self.apply(command);
+        }
+        self.push_all(commands);
+        (self.shape, self.style) = pick((self.shape, self.style));
+    }
+
+    /// Selects the shapes `ids` instead of the selected ones, and shows the style of `shown`, or
+    /// of the first of them, in the toolbar.
+    fn select(&mut self, ids: BTreeSet<u64>, shown: Option<u64>) {
+        if let Some(circle) = shown
+            .or_else(|| ids.first().copied())
+            .and_then(|id| self.circles.get(&id))
+        {
+            (self.shape, self.style) = (circle.shape, circle.style);
+        }
+        self.selected = ids;
+    }
+
+    /// Selects the shapes whose bounds lie inside the rubber band, besides the selected ones if
+    /// shift is held. A band too small to be a drag is a click, which draws a circle instead.
+    fn finish_band(&mut self, band: Band, click_draws: bool) {
+        if band.from.distance(band.to) * self.camera.zoom < CLICK_SLOP {
+            if click_draws {
+                self.create(band.from);
+            }
+            return;
+        }
+
+        let area =
+            Rectangle::new(band.from, Size::ZERO).union(&Rectangle::new(band.to, Size::ZERO));
+        let mut ids = if self.modifiers.shift() {
+            self.selected.clone()
+        } else {
+            BTreeSet::new()
+        };
+        ids.extend(self.circles.iter().filter_map(|(id, circle)| {
+            let bounds = circle.bounds();
+            (area.contains(bounds.position())
+                && area.contains(bounds.position() + Vector::new(bounds.width, bounds.height)))
+            .then_some(*id)
+        }));
+        self.select(ids, None);
+    }
+
+    /// Draws a shape in the toolbar's look at `center`, and selects it.
+    fn create(&mut self, center: Point) {
+        let id = self.next_id;
+        let circle = Circle {
+            center,
+            radius: 50.0,
+            shape: self.shape,
+            style: self.style,
+        };
+
+        self.next_id += 1;
+        self.add(id, circle.clone());
+        self.select(BTreeSet::from([id]), None);
+        self.hovered = Some(id);
+        self.push(Command::Create(id, circle));
+    }
+
+    /// Handles a mouse event at a canvas point, hit-testing and editing in world coordinates.
+    fn mouse(&mut self, mouse: Mouse) {
+        let point = self.camera.to_world(mouse.point);
+        match mouse.event {
+            MouseEvent::LeftPress if self.pan_held => self.panning = Some(mouse.point),
+            MouseEvent::MiddlePress => self.panning = Some(mouse.point),
+            MouseEvent::LeftPress => match self.grid.hit(point, &self.circles) {
+                Some(id) if self.modifiers.shift() => {
+                    let mut ids = self.selected.clone();
+                    if !ids.remove(&id) {
+                        ids.insert(id);
+                    }
+                    self.select(ids, Some(id));
+                }
+                Some(id) => {
+                    if let Some(circle) = self.circles.get(&id) {
+                        self.dragging = Some(Dragging {
+                            id,
+                            from: circle.center,
+                            grab: point - circle.center,
+                        });
+                    }
+                    self.select(BTreeSet::from([id]), None);
+                }
+                None => {
+                    self.banding = Some(Band {
+                        from: point,
+                        to: point,
+                    });
+                }
+            },
+            MouseEvent::LeftRelease | MouseEvent::MiddleRelease | MouseEvent::Exit => {
+                self.panning = None;
+                self.commit_move();
+                if let Some(band) = self.banding.take() {
+                    let band = Band { to: point, ..band };
+                    self.finish_band(band, mouse.event == MouseEvent::LeftRelease);
+                }
+            }
+            MouseEvent::Moved => {
+                self.cursor = mouse.point;
+                if let Some(from) = &mut self.panning {
+                    self.camera.pan(mouse.point - *from);
+                    *from = mouse.point;
+                    self.redraw();
+                    return;
+                }
+                if let Some(dragging) = &self.dragging {
+                    self.set_center(dragging.id, point - dragging.grab);
+                    return;
+                }
+                if let Some(band) = &mut self.banding {
+                    band.to = point;
+                    return;
+                }
+
+                self.hovered = self.grid.hit(point, &self.circles);
+            }
+            MouseEvent::RightPress => {
+                self.commit_pending();
+                if let Some(id) = self.grid.hit(point, &self.circles)
+                    && let Some(radius) = self.circles.get(&id).map(|circle| circle.radius)
+                {
+                    self.select(BTreeSet::from([id]), None);
+                    self.display_size = Some(Resizing { id, from: radius });
+                }
+            }
+        }
+    }
+
+    /// Shows the status of a file action, or why it failed, e.g. "Could not {action}: {error}".
+    fn show(&mut self, result: anyhow::Result<String>, action: &str) {
+        (self.status, self.error) = match result {
+            Ok(status) => (Some(status), None),
+            Err(error) => (None, Some(format!("Could not {action}: {error}"))),
+        };
+    }
+
+    /// Records a new edit, which makes the undone edits unreachable.
+    fn push(&mut self, command: Command) {
+        self.history.push(command);
+        self.undone.clear();
+    }
+
+    /// Records `commands`, if any, as one edit.
+    fn push_all(&mut self, mut commands: Vec<Command>) {
+        match commands.len() {
+            0 => {}
+            1 => self.push(commands.remove(0)),
+            _ => self.push(Command::Batch(commands)),
+        }
+    }
+
+    /// Records the slider moves since the dialog opened, or the slider was last released, as one
+    /// resize.
+    fn commit_resize(&mut self) {
+        let Some(resizing) = &mut self.display_size else {
+            return;
+        };
+        let Some(to) = self.circles.get(&resizing.id).map(|circle| circle.radius) else {
+            return;
+        };
+
+        if (to - resizing.from).abs() > f32::EPSILON {
+            let command = Command::Resize {
+                id: resizing.id,
+                from: resizing.from,
+                to,
+            };
+            resizing.from = to;
+            self.push(command);
+        }
+    }
+
+    /// Records the resize and the drag in progress, so that the next edit doesn't swallow them.
+    /// The slider's wheel and arrow keys change the radius without a release to record it.
+    fn commit_pending(&mut self) {
+        self.commit_resize();
+        self.commit_move();
+    }
+
+    /// Ends the drag, recording it as one move.
+    fn commit_move(&mut self) {
+        let Some(dragging) = self.dragging.take() else {
+            return;
+        };
+        let Some(to) = self.circles.get(&dragging.id).map(|circle| circle.center) else {
+            return;
+        };
+
+        if to != dragging.from {
+            self.push(Command::Move {
+                id: dragging.id,
+                from: dragging.from,
+                to,
+            });
+        }
+    }
+
+    /// The history and file buttons above the canvas.
+    fn toolbar(&self) -> Element<'_, Message> {
+        let mut undo = button("Undo");
+        if !self.history.is_empty() || self.display_size.is_some() {
+            undo = undo.on_press(Message::Undo);
+        }
+
+        let mut redo = button("Redo");
+        if !self.undone.is_empty() {
+            redo = redo.on_press(Message::Redo);
+        }
+
+        let (mut open, mut save, mut export_svg, mut export_png) = (
+            button("Open"),
+            button("Save"),
+            button("Export SVG"),
+            button("Export PNG"),
+        );
+        if !self.file.is_empty() {
+            open = open.on_press(Message::OpenPressed);
+            save = save.on_press(Message::SavePressed);
+            export_svg = export_svg.on_press(Message::ExportSvgPressed);
+            export_png = export_png.on_press(Message::ExportPngPressed);
+        }
+
+        let file = text_input("drawing.json", &self.file)
+            .id("file")
+            .on_input(Message::FileChanged)
+            .width(200);
+
+        row![undo, redo, file, open, save, export_svg, export_png]
+            .padding(10)
+            .spacing(10)
+            .align_y(iced::Alignment::Center)
+            .into()
+    }
+
+    /// The shape and style pickers, for new shapes and the selected one.
+    fn style_bar(&self) -> Element<'_, Message> {
+        row![
+            pick_list(Shape::ALL, Some(self.shape), Message::ShapeSelected),
+            "Fill:",
+            pick_list(
+                PALETTE,
+                NamedColor::find(self.style.fill),
+                Message::FillSelected
+            ),
+            "Stroke:",
+            pick_list(
+                PALETTE,
+                NamedColor::find(self.style.stroke),
+                Message::StrokeSelected
+            ),
+            pick_list(
+                STROKE_WIDTHS,
+                Some(style::StrokeWidth(self.style.stroke_width)),
+                Message::StrokeWidthSelected
+            ),
+            button("Fit all").on_press(Message::FitAll),
+            text!("{}%", (self.camera.zoom * 100.0).round_ties_even()),
+        ]
+        .padding([0, 10])
+        .spacing(10)
+        .align_y(iced::Alignment::Center)
+        .into()
+    }
+
+    #[must_use]
+    pub fn view(&self) -> Element<'_, Message> {
+        let mut stack = Stack::new();
+
+        let mut column_1 = Column::new();
+
+        column_1 = column_1.push(self.toolbar());
+        column_1 = column_1.push(self.style_bar());
+
+        if let Some(status) = &self.status {
+            column_1 = column_1.push(text(status).width(CANVAS_SIZE));
+        }
+
+        if let Some(error) = &self.error {
+            column_1 = column_1.push(text(error).style(text::danger).width(CANVAS_SIZE));
+        }
+
+        let mut area = mouse_area(center("").style(container::rounded_box));
+        if self.panning.is_some() {
+            area = area.interaction(Interaction::Grabbing);
+        } else if self.pan_held {
+            area = area.interaction(Interaction::Grab);
+        }
+
+        stack = stack.push(
+            center(
+                area.on_move(|point| {
+                    Message::Mouse(Mouse {
+                        event: MouseEvent::Moved,
+                        point,
+                    })
+                })
+                .on_press(|point| {
+                    Message::Mouse(Mouse {
+                        event: MouseEvent::LeftPress,
+                        point,
+                    })
+                })
+                .on_release(|point| {
+                    Message::Mouse(Mouse {
+                        event: MouseEvent::LeftRelease,
+                        point,
+                    })
+                })
+                .on_exit(|point| {
+                    Message::Mouse(Mouse {
+                        event: MouseEvent::Exit,
+                        point,
+                    })
+                })
+                .on_right_press(|point| {
+                    Message::Mouse(Mouse {
+                        event: MouseEvent::RightPress,
+                        point,
+                    })
+                })
+                .on_middle_press(|point| {
+                    Message::Mouse(Mouse {
+                        event: MouseEvent::MiddlePress,
+                        point,
+                    })
+                })
+                .on_middle_release(|point| {
+                    Message::Mouse(Mouse {
+                        event: MouseEvent::MiddleRelease,
+                        point,
+                    })
+                })
+                .on_scroll(Message::Scrolled),
+            )
+            .width(CANVAS_SIZE)
+            .height(CANVAS_SIZE),
+        );
+
+        stack = stack.push(Canvas::new(self).width(CANVAS_SIZE).height(CANVAS_SIZE));
+
+        if let Some(resizing) = &self.display_size
+            && let Some(circle) = self.circles.get(&resizing.id)
+        {
+            let mut column_2 = Column::new();
+            column_2 = column_2.push(text!(
+                "Adjust radius of circle at ({}, {}).",
+                circle.center.x.round_ties_even(),
+                circle.center.y.round_ties_even()
+            ));
+            column_2 = column_2.push(
+                slider(RADIUS, circle.radius, Message::SizeChange)
+                    .on_release(Message::SizeReleased),
+            );
+
+            stack = stack.push(
+                card(
+                    text!("Circle Radius {}", circle.radius.round_ties_even()),
+                    column_2,
+                )
+                .style(iced_aw::style::card::primary)
+                .on_close(Message::CloseSize),
+            );
+        }
+
+        column_1.push(stack).into()
+    }
+
+    /// Tracks whether the pan key and the modifiers are held, for panning and adding to the
+    /// selection, and runs the drawer's shortcuts.
+    pub fn subscription(&self) -> Subscription<Message> {
+        keyboard::listen().filter_map(|event| match event {
+            keyboard::Event::KeyPressed { .. } => {
+                match keys::action("circle_drawer", KEYS, &event)? {
+                    "copy" => Some(Message::Copy),
+                    "delete" => Some(Message::Delete),
+                    "pan" => Some(Message::PanHeld(true)),
+                    "paste" => Some(Message::Paste),
+                    "redo" => Some(Message::Redo),
+                    "undo" => Some(Message::Undo),
+                    _ => None,
+                }
+            }
+            keyboard::Event::KeyReleased { .. } => (keys::released("circle_drawer", KEYS, &event)?
+                == "pan")
+                .then_some(Message::PanHeld(false)),
+            keyboard::Event::ModifiersChanged(modifiers) => {
+                Some(Message::ModifiersChanged(modifiers))
+            }
+        })
+    }
+}
+
+#[derive(Clone, Debug)]
+pub enum Message {
+    CloseSize,
+    Copy,
+    Delete,
+    ExportPngPressed,
+    ExportSvgPressed,
+    FileChanged(String),
+    FillSelected(NamedColor),
+    FitAll,
+    ModifiersChanged(Modifiers),
+    OpenPressed,
+    PanHeld(bool),
+    Paste,
+    Pasted(Option<String>),
+    SavePressed,
+    ShapeSelected(Shape),
+    StrokeSelected(NamedColor),
+    StrokeWidthSelected(style::StrokeWidth),
+    Mouse(Mouse),
+    Redo,
+    Scrolled(ScrollDelta),
+    SizeChange(f32),
+    SizeReleased,
+    Undo,
+}
+
+/// What the mouse did on the canvas.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MouseEvent {
+    LeftPress,
+    LeftRelease,
+    MiddlePress,
+    MiddleRelease,
+    RightPress,
+    Moved,
+    /// The cursor left the canvas.
+    Exit,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Mouse {
+    pub event: MouseEvent,
+    /// Where on the canvas, in screen pixels.
+    pub point: Point,
+}
+
+impl<Message> Program<Message> for CircleDrawer {
+    type State = ();
+
+    fn draw(
+        &self,
+        (): &(),
+        renderer: &iced::Renderer,
+        _: &Theme,
+        bounds: Rectangle,
+        _: iced::mouse::Cursor,
+    ) -> Vec<Geometry> {
+        let shapes = self.cache.draw(renderer, bounds.size(), |frame| {
+            for circle in self.circles.values() {
+                draw(frame, &self.camera.project(circle));
+            }
+        });
+
+        // The outlines and the band change with every pointer move, so they are drawn apart
+        // from the cached shapes, each outlined shape again over its outline.
+        let mut overlay = Frame::new(renderer, bounds.size());
+        let hovered = self.hovered.filter(|id| !self.selected.contains(id));
+        let outlined = self
+            .selected
+            .iter()
+            .map(|id| (*id, SELECTION))
+            .chain(hovered.map(|id| (id, HOVER)));
+        for (id, color) in outlined {
+            if let Some(circle) = self.circles.get(&id) {
+                let circle = self.camera.project(circle);
+                overlay.stroke(
+                    &circle.path(),
+                    Stroke::default()
+                        .with_color(color)
+                        .with_width(circle.outline_width()),
+                );
+                draw(&mut overlay, &circle);
+            }
+        }
+
+        if let Some(band) = &self.banding {
+            let (from, to) = (
+                self.camera.to_screen(band.from),
+                self.camera.to_screen(band.to),
+            );
+            let band = Path::rectangle(
+                Point::new(from.x.min(to.x), from.y.min(to.y)),
+                Size::new((to.x - from.x).abs(), (to.y - from.y).abs()),
+            );
+            overlay.fill(&band, HOVER.scale_alpha(0.5));
+            overlay.stroke(&band, Stroke::default().with_color(SELECTION));
+        }
+
+        vec![shapes, overlay.into_geometry()]
+    }
+}
+
+/// Fills and strokes `circle`, already on screen, in its own style.
+fn draw(frame: &mut Frame, circle: &Circle) {
+    let path = circle.path();
+    let style = circle.style;
+
+    frame.fill(&path, style.fill);
+    if style.stroke_width > 0.0 {
+        frame.stroke(
+            &path,
+            Stroke::default()
+                .with_color(style.stroke)
+                .with_width(style.stroke_width),
+        );
+    }
+}
+
+/// The area all of `circles` cover, with their strokes, or none for no circles.
+#[must_use]
+pub fn drawing_bounds(circles: &[Circle]) -> Option<Rectangle> {
+    circles
+        .iter()
+        .map(Circle::bounds)
+        .reduce(|a, b| a.union(&b))
+}
+
+/// The world area that SVG and PNG exports show: the canvas as first shown, from (0, 0) to
+/// ([`CANVAS_SIZE`], [`CANVAS_SIZE`]), grown to whole units that take in every shape, wherever
+/// it was panned to.
+///
+/// Exports draw only the shapes: the selection and hover outlines and the rubber band are left
+/// out, since they are not part of the drawing.
+#[must_use]
+pub fn export_area(circles: &[Circle]) -> Rectangle {
+    let canvas = Rectangle::new(Point::ORIGIN, Size::new(CANVAS_SIZE, CANVAS_SIZE));
+    let area = drawing_bounds(circles).map_or(canvas, |bounds| canvas.union(&bounds));
+
+    let (left, top) = (area.x.floor(), area.y.floor());
+    let (right, bottom) = ((area.x + area.width).ceil(), (area.y + area.height).ceil());
+    Rectangle::new(Point::new(left, top), Size::new(right - left, bottom - top))
+}
+
+/// A shape on the canvas. Its radius is half its width.
+#[derive(Clone, Debug, Default)]
+pub struct Circle {
+    pub center: Point,
+    pub radius: f32,
+    pub shape: Shape,
+    pub style: Style,
+}
+
+impl Circle {
+    /// Half the width and height of the shape.
+    #[must_use]
+    pub fn half_size(&self) -> Vector {
+        match self.shape {
+            Shape::Circle => Vector::new(self.radius, self.radius),
+            Shape::Ellipse | Shape::Rectangle => Vector::new(self.radius, self.radius / 2.0),
+        }
+    }
+
+    /// The top left corner of the shape's bounding box.
+    #[must_use]
+    pub fn top_left(&self) -> Point {
+        self.center - self.half_size()
+    }
+
+    /// The area the shape covers, with its stroke.
+    #[must_use]
+    pub fn bounds(&self) -> Rectangle {
+        let half = self.half_size() + Vector::new(1.0, 1.0) * (self.style.stroke_width / 2.0);
+        Rectangle::new(self.center - half, Size::new(2.0 * half.x, 2.0 * half.y))
+    }
+
+    /// The width of the selection outline, which reaches [`OUTLINE_WIDTH`] beyond the stroke.
+    #[must_use]
+    pub fn outline_width(&self) -> f32 {
+        self.style.stroke_width + 2.0 * OUTLINE_WIDTH
+    }
+
+    /// Whether `point` is inside the shape.
+    #[must_use]
+    pub fn contains(&self, point: Point) -> bool {
+        let half = self.half_size();
+        let (x, y) = (
+            (point.x - self.center.x) / half.x,
+            (point.y - self.center.y) / half.y,
+        );
+
+        match self.shape {
+            Shape::Circle | Shape::Ellipse => x * x + y * y < 1.0,
+            Shape::Rectangle => x.abs() < 1.0 && y.abs() < 1.0,
+        }
+    }
+
+    fn path(&self) -> Path {
+        match self.shape {
+            Shape::Circle => Path::circle(self.center, self.radius),
+            Shape::Ellipse => Path::new(|builder| {
+                builder.ellipse(Elliptical {
+                    center: self.center,
+                    radii: self.half_size(),
+                    rotation: Radians(0.0),
+                    start_angle: Radians(0.0),
+                    end_angle: Radians(2.0 * std::f32::consts::PI),
+                });
+                builder.close();
+            }),
+            Shape::Rectangle => {
+                let half = self.half_size();
+                Path::rectangle(self.top_left(), Size::new(2.0 * half.x, 2.0 * half.y))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use iced::{Color, Point, mouse::ScrollDelta};
+
+    // Not a glob import, since `Program::update` would shadow `CircleDrawer::update`.
+    use super::{
+        CircleDrawer, Message, Mouse, MouseEvent, NamedColor, PALETTE, STROKE_WIDTHS, Shape, Style,
+        document,
+    };
+
+    fn mouse(event: MouseEvent, x: f32, y: f32) -> Message {
+        Message::Mouse(Mouse {
+            event,
+            point: Point::new(x, y),
+        })
+    }
+
+    /// Presses and releases the left button without moving, which draws on empty canvas.
+    fn click(drawer: &mut CircleDrawer, x: f32, y: f32) {
+        let _ = drawer.update(mouse(MouseEvent::LeftPress, x, y));
+        let _ = drawer.update(mouse(MouseEvent::LeftRelease, x, y));
+    }
+
+    fn radii(drawer: &CircleDrawer) -> Vec<f32> {
+        drawer
+            .circles()
+            .iter()
+            .map(|circle| circle.radius)
+            .collect()
+    }
+
+    #[test]
+    fn slider_drag_is_one_step() {
+        let mut drawer = CircleDrawer::default();
+        click(&mut drawer, 100.0, 100.0);
+        let _ = drawer.update(mouse(MouseEvent::Moved, 100.0, 100.0));
+        let _ = drawer.update(mouse(MouseEvent::RightPress, 100.0, 100.0));
+
+        for radius in [40.0, 30.0, 20.0] {
+            let _ = drawer.update(Message::SizeChange(radius));
+        }
+        let _ = drawer.update(Message::SizeReleased);
+        let _ = drawer.update(Message::SizeChange(80.0));
+        let _ = drawer.update(Message::CloseSize);
+        assert_eq!(radii(&drawer), [80.0]);
+
+        let _ = drawer.update(Message::Undo);
+        assert_eq!(radii(&drawer), [20.0]);
+        let _ = drawer.update(Message::Undo);
+        assert_eq!(radii(&drawer), [50.0]);
+        let _ = drawer.update(Message::Undo);
+        assert!(drawer.circles().is_empty());
+
+        for expected in [&[50.0][..], &[20.0], &[80.0]] {
+            let _ = drawer.update(Message::Redo);
+            assert_eq!(radii(&drawer), expected);
+        }
+    }
+
+    #[test]
+    fn resizing_another_circle_keeps_the_unreleased_resize() {
+        let mut drawer = CircleDrawer::default();
+        click(&mut drawer, 100.0, 100.0);
+        click(&mut drawer, 300.0, 300.0);
+
+        // The wheel or the arrow keys move the slider without releasing it.
+        let _ = drawer.update(mouse(MouseEvent::RightPress, 100.0, 100.0));
+        let _ = drawer.update(Message::SizeChange(20.0));
+        let _ = drawer.update(mouse(MouseEvent::RightPress, 300.0, 300.0));
+        let _ = drawer.update(Message::SizeChange(30.0));
+        let _ = drawer.update(Message::CloseSize);
+        assert_eq!(radii(&drawer), [20.0, 30.0]);
+
+        let _ = drawer.update(Message::Undo);
+        assert_eq!(radii(&drawer), [20.0, 50.0]);
+        let _ = drawer.update(Message::Undo);
+        assert_eq!(radii(&drawer), [50.0, 50.0]);
+
+        // Deleting with the dialog open records the resize first.
+        let _ = drawer.update(mouse(MouseEvent::RightPress, 100.0, 100.0));
+        let _ = drawer.update(Message::SizeChange(40.0));
+        let _ = drawer.update(Message::Delete);
+        let _ = drawer.update(Message::Undo);
+        assert_eq!(radii(&drawer), [40.0, 50.0]);
+        let _ = drawer.update(Message::Undo);
+        assert_eq!(radii(&drawer), [50.0, 50.0]);
+    }
+
+    #[test]
+    fn new_edits_clear_redo() {
+        let mut drawer = CircleDrawer::default();
+        click(&mut drawer, 100.0, 100.0);
+        let _ = drawer.update(mouse(MouseEvent::RightPress, 100.0, 100.0));
+        let _ = drawer.update(Message::SizeChange(20.0));
+        let _ = drawer.update(Message::Undo);
+        assert_eq!(radii(&drawer), [50.0]);
+
+        click(&mut drawer, 300.0, 300.0);
+        let _ = drawer.update(Message::Redo);
+        assert_eq!(radii(&drawer), [50.0, 50.0]);
+    }
+
+    #[test]
+    fn saves_and_opens_documents() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("drawing.json");
+
+        let mut drawer = CircleDrawer::default();
+        click(&mut drawer, 100.0, 100.0);
+        let _ = drawer.update(Message::FileChanged(path.display().to_string()));
+        let _ = drawer.update(Message::SavePressed);
+
+        let mut opened = CircleDrawer::default();
+        click(&mut opened, 300.0, 300.0);
+        let _ = opened.update(Message::FileChanged(path.display().to_string()));
+        let _ = opened.update(Message::OpenPressed);
+        assert_eq!(radii(&opened), [50.0]);
+        assert!(opened.circles()[0].center == Point::new(100.0, 100.0));
+
+        // The history belongs to the old drawing.
+        let _ = opened.update(Message::Undo);
+        assert_eq!(radii(&opened), [50.0]);
+
+        std::fs::write(&path, "{}").unwrap();
+        let _ = opened.update(Message::OpenPressed);
+        assert_eq!(radii(&opened), [50.0]);
+    }
+
+    #[test]
+    fn palette_colors_survive_saving_and_pasting() {
+        let (gray, red) = (PALETTE[2], PALETTE[3]);
+        assert!(gray.color != Color::from_rgba8(128, 128, 128, 1.0));
+
+        let mut drawer = CircleDrawer::default();
+        let _ = drawer.update(Message::FillSelected(gray));
+        let _ = drawer.update(Message::StrokeSelected(red));
+        click(&mut drawer, 100.0, 100.0);
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("drawing.json");
+        drawer.save(&path).unwrap();
+        let mut opened = CircleDrawer::default();
+        opened.open(&path).unwrap();
+
+        let _ = drawer.update(Message::Pasted(Some(
+            document::to_json(&drawer.selected()).unwrap(),
+        )));
+        for circle in [&opened.circles()[0], &drawer.circles()[1]] {
+            assert_eq!(NamedColor::find(circle.style.fill), Some(gray));
+            assert_eq!(NamedColor::find(circle.style.stroke), Some(red));
+        }
+
+        // The pasted shape is selected, and the toolbar names its colors.
+        assert_eq!(NamedColor::find(drawer.style.fill), Some(gray));
+    }
+
+    fn centers(drawer: &CircleDrawer) -> Vec<Point> {
+        drawer
+            .circles()
+            .iter()
+            .map(|circle| circle.center)
+            .collect()
+    }
+
+    #[test]
+    fn drag_is_one_move() {
+        let mut drawer = CircleDrawer::default();
+        click(&mut drawer, 100.0, 100.0);
+
+        // Grabbed off center, the circle keeps its offset from the cursor.
+        let _ = drawer.update(mouse(MouseEvent::Moved, 110.0, 110.0));
+        let _ = drawer.update(mouse(MouseEvent::LeftPress, 110.0, 110.0));
+        let _ = drawer.update(mouse(MouseEvent::Moved, 160.0, 130.0));
+        assert_eq!(centers(&drawer), [Point::new(150.0, 120.0)]);
+        let _ = drawer.update(mouse(MouseEvent::Moved, 210.0, 160.0));
+        let _ = drawer.update(mouse(MouseEvent::LeftRelease, 210.0, 160.0));
+        assert_eq!(centers(&drawer), [Point::new(200.0, 150.0)]);
+
+        let _ = drawer.update(Message::Undo);
+        assert_eq!(centers(&drawer), [Point::new(100.0, 100.0)]);
+        let _ = drawer.update(Message::Redo);
+        assert_eq!(centers(&drawer), [Point::new(200.0, 150.0)]);
+
+        let _ = drawer.update(Message::Undo);
+        let _ = drawer.update(Message::Undo);
+        assert!(drawer.circles().is_empty());
+    }
+
+    #[test]
+    fn click_outside_circles_creates() {
+        let mut drawer = CircleDrawer::default();
+        click(&mut drawer, 100.0, 100.0);
+        click(&mut drawer, 100.0, 100.0);
+        assert_eq!(centers(&drawer), [Point::new(100.0, 100.0)]);
+
+        click(&mut drawer, 300.0, 300.0);
+        assert_eq!(
+            centers(&drawer),
+            [Point::new(100.0, 100.0), Point::new(300.0, 300.0)]
+        );
+    }
+
+    #[test]
+    fn leaving_the_canvas_ends_the_drag() {
+        let mut drawer = CircleDrawer::default();
+        click(&mut drawer, 100.0, 100.0);
+        let _ = drawer.update(mouse(MouseEvent::LeftPress, 100.0, 100.0));
+        let _ = drawer.update(mouse(MouseEvent::Moved, 50.0, 100.0));
+        let _ = drawer.update(mouse(MouseEvent::Exit, 0.0, 100.0));
+        let _ = drawer.update(mouse(MouseEvent::Moved, 20.0, 100.0));
+
+        assert_eq!(centers(&drawer), [Point::new(50.0, 100.0)]);
+        let _ = drawer.update(Message::Undo);
+        assert_eq!(centers(&drawer), [Point::new(100.0, 100.0)]);
+    }
+
+    #[test]
+    fn hovering_does_not_select() {
+        let mut drawer = CircleDrawer::default();
+        click(&mut drawer, 100.0, 100.0);
+        click(&mut drawer, 300.0, 300.0);
+        let _ = drawer.update(mouse(MouseEvent::Moved, 100.0, 100.0));
+
+        assert_eq!(selected(&drawer), [false, true]);
+    }
+
+    #[test]
+    fn restyling_the_selection_is_undoable() {
+        let mut drawer = CircleDrawer::default();
+        click(&mut drawer, 100.0, 100.0);
+        let _ = drawer.update(Message::ShapeSelected(Shape::Rectangle));
+        let _ = drawer.update(Message::FillSelected(PALETTE[3]));
+        assert_eq!(drawer.circles()[0].shape, Shape::Rectangle);
+        assert_eq!(drawer.circles()[0].style.fill, PALETTE[3].color);
+
+        let _ = drawer.update(Message::Undo);
+        assert_eq!(drawer.circles()[0].style, Style::default());
+        let _ = drawer.update(Message::Undo);
+        assert_eq!(drawer.circles()[0].shape, Shape::Circle);
+        let _ = drawer.update(Message::Redo);
+        let _ = drawer.update(Message::Redo);
+        assert_eq!(drawer.circles()[0].style.fill, PALETTE[3].color);
+
+        // New shapes take the toolbar's look.
+        click(&mut drawer, 300.0, 300.0);
+        assert_eq!(drawer.circles()[1].shape, Shape::Rectangle);
+        assert_eq!(drawer.circles()[1].style.fill, PALETTE[3].color);
+    }
+
+    #[test]
+    fn edits_in_world_coordinates() {
+        let mut drawer = CircleDrawer::default();
+        let _ = drawer.update(mouse(MouseEvent::Moved, 0.0, 0.0));
+        let _ = drawer.update(Message::Scrolled(ScrollDelta::Lines { x: 0.0, y: 10.0 }));
+        let zoom = drawer.camera.zoom;
+
+        let _ = drawer.update(mouse(MouseEvent::MiddlePress, 400.0, 400.0));
+        let _ = drawer.update(mouse(MouseEvent::Moved, 500.0, 300.0));
+        let _ = drawer.update(mouse(MouseEvent::MiddleRelease, 500.0, 300.0));
+
+        click(&mut drawer, 500.0, 300.0);
+        let center = drawer.circles()[0].center;
+        assert!(center.distance(Point::new(400.0 / zoom, 400.0 / zoom)) < 1e-3);
+
+        // The circle is hit where it is drawn.
+        let _ = drawer.update(mouse(MouseEvent::RightPress, 500.0 + 40.0 * zoom, 300.0));
+        assert!(drawer.display_size.is_some());
+    }
+
+    #[test]
+    fn space_drag_pans_instead_of_drawing() {
+        let mut drawer = CircleDrawer::default();
+        let _ = drawer.update(Message::PanHeld(true));
+        let _ = drawer.update(mouse(MouseEvent::LeftPress, 100.0, 100.0));
+        let _ = drawer.update(mouse(MouseEvent::Moved, 150.0, 100.0));
+        let _ = drawer.update(mouse(MouseEvent::LeftRelease, 150.0, 100.0));
+        let _ = drawer.update(Message::PanHeld(false));
+        assert!(drawer.circles().is_empty());
+
+        click(&mut drawer, 150.0, 100.0);
+        assert_eq!(centers(&drawer), [Point::new(100.0, 100.0)]);
+    }
+
+    #[test]
+    fn outlines_and_bands_keep_the_drawn_shapes() {
+        let mut drawer = CircleDrawer::default();
+        click(&mut drawer, 100.0, 100.0);
+        click(&mut drawer, 300.0, 300.0);
+        let redraws = drawer.redraws;
+
+        let _ = drawer.update(mouse(MouseEvent::Moved, 100.0, 100.0));
+        let _ = drawer.update(mouse(MouseEvent::Moved, 500.0, 500.0));
+        let _ = drawer.update(mouse(MouseEvent::LeftPress, 20.0, 20.0));
+        let _ = drawer.update(mouse(MouseEvent::Moved, 400.0, 400.0));
+        let _ = drawer.update(mouse(MouseEvent::LeftRelease, 400.0, 400.0));
+        assert_eq!(selected(&drawer), [true, true]);
+        assert_eq!(drawer.redraws, redraws);
+
+        let _ = drawer.update(Message::Scrolled(ScrollDelta::Lines { x: 0.0, y: 1.0 }));
+        assert!(drawer.redraws > redraws);
+    }
+
+    fn selected(drawer: &CircleDrawer) -> Vec<bool> {
+        drawer
+            .circles
+            .keys()
+            .map(|id| drawer.selected.contains(id))
+            .collect()
+    }
+
+    #[test]
+    fn rubber_band_and_shift_click_select() {
+        let mut drawer = CircleDrawer::default();
+        for x in [100.0, 250.0, 600.0] {
+            click(&mut drawer, x, 100.0);
+        }
+
+        let _ = drawer.update(mouse(MouseEvent::LeftPress, 20.0, 20.0));
+        let _ = drawer.update(mouse(MouseEvent::Moved, 400.0, 200.0));
+        let _ = drawer.update(mouse(MouseEvent::LeftRelease, 400.0, 200.0));
+        assert_eq!(selected(&drawer), [true, true, false]);
+        assert_eq!(drawer.circles().len(), 3);
+
+        let shift = iced::keyboard::Modifiers::SHIFT;
+        let _ = drawer.update(Message::ModifiersChanged(shift));
+        click(&mut drawer, 600.0, 100.0);
+        click(&mut drawer, 100.0, 100.0);
+        assert_eq!(selected(&drawer), [false, true, true]);
+    }
+
+    #[test]
+    fn deleting_and_pasting_are_undoable() {
+        let mut drawer = CircleDrawer::default();
+        click(&mut drawer, 100.0, 100.0);
+        click(&mut drawer, 300.0, 100.0);
+        let _ = drawer.update(mouse(MouseEvent::LeftPress, 20.0, 20.0));
+        let _ = drawer.update(mouse(MouseEvent::LeftRelease, 400.0, 200.0));
+        let copied = document::to_json(&drawer.circles()).unwrap();
+
+        let _ = drawer.update(Message::Pasted(Some(copied)));
+        assert_eq!(
+            centers(&drawer),
+            [
+                Point::new(100.0, 100.0),
+                Point::new(300.0, 100.0),
+                Point::new(120.0, 120.0),
+                Point::new(320.0, 120.0),
+            ]
+        );
+        assert_eq!(selected(&drawer), [false, false, true, true]);
+
+        let _ = drawer.update(Message::Delete);
+        assert_eq!(drawer.circles().len(), 2);
+        let _ = drawer.update(Message::Undo);
+        assert_eq!(drawer.circles().len(), 4);
+        let _ = drawer.update(Message::Undo);
+        assert_eq!(drawer.circles().len(), 2);
+        let _ = drawer.update(Message::Redo);
+        let _ = drawer.update(Message::Redo);
+        assert_eq!(drawer.circles().len(), 2);
+
+        let _ = drawer.update(Message::Pasted(Some("not a drawing".to_string())));
+        assert_eq!(drawer.circles().len(), 2);
+        assert!(drawer.error.is_some());
+    }
+
+    #[test]
+    fn restyling_several_shapes_is_one_step() {
+        let mut drawer = CircleDrawer::default();
+        click(&mut drawer, 100.0, 100.0);
+        click(&mut drawer, 300.0, 100.0);
+        let _ = drawer.update(Message::ShapeSelected(Shape::Rectangle));
+        let _ = drawer.update(Message::FillSelected(PALETTE[3]));
+        let _ = drawer.update(mouse(MouseEvent::LeftPress, 20.0, 20.0));
+        let _ = drawer.update(mouse(MouseEvent::LeftRelease, 400.0, 200.0));
+
+        let looks = |drawer: &CircleDrawer| -> Vec<(Shape, Style)> {
+            drawer
+                .circles()
+                .iter()
+                .map(|circle| (circle.shape, circle.style))
+                .collect()
+        };
+        let before = looks(&drawer);
+        assert_eq!(before[0], (Shape::Circle, Style::default()));
+        assert_eq!(
+            (before[1].0, before[1].1.fill),
+            (Shape::Rectangle, PALETTE[3].color)
+        );
+
+        let _ = drawer.update(Message::StrokeWidthSelected(STROKE_WIDTHS[4]));
+        let after = looks(&drawer);
+        for (before, after) in before.iter().zip(&after) {
+            assert_eq!(
+                *after,
+                (
+                    before.0,
+                    Style {
+                        stroke_width: STROKE_WIDTHS[4].0,
+                        ..before.1
+                    }
+                )
+            );
+        }
+        // The toolbar shows the first selected shape.
+        assert_eq!((drawer.shape, drawer.style), after[0]);
+
+        let _ = drawer.update(Message::Undo);
+        assert_eq!(looks(&drawer), before);
+        let _ = drawer.update(Message::Redo);
+        assert_eq!(looks(&drawer), after);
+    }
+}
@@ -0,0 +1,164 @@
+//! The view transform between the canvas, in screen pixels, and the drawing, in world coordinates.
+
+use std::ops::RangeInclusive;
+
+use iced::{Point, Vector};
+
+use super::{CANVAS_SIZE, Circle, drawing_bounds};
+
+/// The zoom levels the wheel can reach.
+pub const ZOOM: RangeInclusive<f32> = 0.1..=10.0;
+
+/// How much one wheel notch zooms in.
+const ZOOM_STEP: f32 = 1.1;
+
+/// The space "fit all" leaves around the drawing, in screen pixels.
+const FIT_MARGIN: f32 = 20.0;
+
+/// Which part of the drawing the canvas shows. The default shows world coordinates as pixels.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Camera {
+    /// The world point at the canvas's top left corner.
+    pub offset: Vector,
+    /// Screen pixels per world unit.
+    pub zoom: f32,
+}
+
+impl Default for Camera {
+    fn default() -> Self {
+        Camera {
+            offset: Vector::ZERO,
+            zoom: 1.0,
+        }
+    }
+}
+
+impl Camera {
+    /// The world point under the canvas point `screen`.
+    #[must_use]
+    pub fn to_world(&self, screen: Point) -> Point {
+        Point::new(
+            self.offset.x + screen.x / self.zoom,
+            self.offset.y + screen.y / self.zoom,
+        )
+    }
+
+    /// The canvas point showing the world point `world`.
+    #[must_use]
+    pub fn to_screen(&self, world: Point) -> Point {
+        Point::new(
+            (world.x - self.offset.x) * self.zoom,
+            (world.y - self.offset.y) * self.zoom,
+        )
+    }
+
+    /// `circle` as it is drawn on the canvas, with its size and stroke scaled.
+    #[must_use]
+    pub fn project(&self, circle: &Circle) -> Circle {
+        let mut projected = circle.clone();
+        projected.center = self.to_screen(circle.center);
+        projected.radius *= self.zoom;
+        projected.style.stroke_width *= self.zoom;
+        projected
+    }
+
+    /// Zooms by `notches` wheel notches, negative to zoom out, keeping the world point under the
+    /// canvas point `screen` in place.
+    pub fn zoom_at(&mut self, screen: Point, notches: f32) {
+        let world = self.to_world(screen);
+        self.zoom = (self.zoom * ZOOM_STEP.powf(notches)).clamp(*ZOOM.start(), *ZOOM.end());
+        self.offset = Vector::new(
+            world.x - screen.x / self.zoom,
+            world.y - screen.y / self.zoom,
+        );
+    }
+
+    /// Moves the drawing by `screen` pixels.
+    pub fn pan(&mut self, screen: Vector) {
+        self.offset -= screen * (1.0 / self.zoom);
+    }
+
+    /// A camera centered on `circles` that shows all of them, or the default for none.
+    #[must_use]
+    pub fn fit(circles: &[Circle]) -> Self {
+        let Some(bounds) = drawing_bounds(circles) else {
+            return Camera::default();
+        };
+
+        let room = CANVAS_SIZE - 2.0 * FIT_MARGIN;
+        let zoom = (room / bounds.width)
+            .min(room / bounds.height)
+            .clamp(*ZOOM.start(), *ZOOM.end());
+        let center = bounds.center();
+        Camera {
+            offset: Vector::new(
+                center.x - CANVAS_SIZE / 2.0 / zoom,
+                center.y - CANVAS_SIZE / 2.0 / zoom,
+            ),
+            zoom,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_near(a: Point, b: Point) {
+        assert!(a.distance(b) < 1e-3, "{a:?} is not {b:?}");
+    }
+
+    #[test]
+    fn zooming_keeps_the_cursor_still() {
+        let mut camera = Camera::default();
+        let cursor = Point::new(200.0, 300.0);
+
+        camera.zoom_at(cursor, 5.0);
+        assert!(camera.zoom > 1.5);
+        assert_near(camera.to_world(cursor), cursor);
+        assert_near(
+            camera.to_screen(camera.to_world(Point::ORIGIN)),
+            Point::ORIGIN,
+        );
+
+        camera.zoom_at(cursor, -1_000.0);
+        assert!((camera.zoom - ZOOM.start()).abs() < f32::EPSILON);
+        assert_near(camera.to_world(cursor), cursor);
+    }
+
+    #[test]
+    fn panning_follows_the_cursor() {
+        let mut camera = Camera::default();
+        camera.zoom_at(Point::ORIGIN, 7.0);
+        let world = camera.to_world(Point::new(100.0, 100.0));
+
+        camera.pan(Vector::new(50.0, -20.0));
+        assert_near(camera.to_screen(world), Point::new(150.0, 80.0));
+    }
+
+    #[test]
+    fn fits_all_circles() {
+        let circles = [
+            Circle {
+                center: Point::new(-1_000.0, 0.0),
+                radius: 50.0,
+                ..Circle::default()
+            },
+            Circle {
+                center: Point::new(3_000.0, 500.0),
+                radius: 50.0,
+                ..Circle::default()
+            },
+        ];
+        let camera = Camera::fit(&circles);
+
+        for circle in &circles {
+            let screen = camera.project(circle);
+            let half = screen.half_size();
+            assert!(screen.center.x - half.x >= FIT_MARGIN - 1.0);
+            assert!(screen.center.x + half.x <= CANVAS_SIZE - FIT_MARGIN + 1.0);
+            assert!((0.0..=CANVAS_SIZE).contains(&screen.center.y));
+        }
+        assert_eq!(Camera::fit(&[]), Camera::default());
+    }
+}
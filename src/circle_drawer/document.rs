@@ -0,0 +1,264 @@
+//! Saving circle drawings to a versioned JSON document.
+
+use std::{fs, path::Path};
+
+use iced::{Color, Point};
+use serde::{Deserialize, Serialize};
+
+use super::{
+    Circle, RADIUS,
+    style::{PALETTE, Shape, Style},
+};
+
+/// The document format written by this version. Older versions are read too.
+///
+/// Version 2 added each shape's type and style. Version 1 shapes are circles in the default style.
+pub const VERSION: u32 = 2;
+
+/// The widest stroke a document may ask for.
+const MAX_STROKE_WIDTH: f32 = 50.0;
+
+#[derive(Debug, Deserialize, Serialize)]
+struct Document {
+    version: u32,
+    circles: Vec<SavedCircle>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct SavedCircle {
+    x: f32,
+    y: f32,
+    radius: f32,
+    #[serde(default)]
+    shape: Shape,
+    /// RGBA colors.
+    #[serde(default = "default_fill")]
+    fill: [u8; 4],
+    #[serde(default = "default_stroke")]
+    stroke: [u8; 4],
+    #[serde(default = "default_stroke_width")]
+    stroke_width: f32,
+}
+
+fn default_fill() -> [u8; 4] {
+    Style::default().fill.into_rgba8()
+}
+
+fn default_stroke() -> [u8; 4] {
+    Style::default().stroke.into_rgba8()
+}
+
+fn default_stroke_width() -> f32 {
+    Style::default().stroke_width
+}
+
+/// The color saved as `rgba`. A palette color comes back as exactly its palette entry, which
+/// rounding to bytes would otherwise lose, so the toolbar still names it.
+fn color(rgba: [u8; 4]) -> Color {
+    PALETTE
+        .iter()
+        .map(|named| named.color)
+        .find(|color| color.into_rgba8() == rgba)
+        .unwrap_or_else(|| {
+            let [r, g, b, a] = rgba;
+            Color::from_rgba8(r, g, b, f32::from(a) / 255.0)
+        })
+}
+
+/// Only the version, read first so a newer document gets a clear error.
+#[derive(Deserialize)]
+struct Version {
+    version: u32,
+}
+
+/// Writes the shapes' centers, radii, types and styles as JSON.
+///
+/// # Errors
+///
+/// Errors if the document can't be serialized.
+pub fn to_json(circles: &[Circle]) -> anyhow::Result<String> {
+    let document = Document {
+        version: VERSION,
+        circles: circles
+            .iter()
+            .map(|circle| SavedCircle {
+                x: circle.center.x,
+                y: circle.center.y,
+                radius: circle.radius,
+                shape: circle.shape,
+                fill: circle.style.fill.into_rgba8(),
+                stroke: circle.style.stroke.into_rgba8(),
+                stroke_width: circle.style.stroke_width,
+            })
+            .collect(),
+    };
+
+    Ok(serde_json::to_string_pretty(&document)?)
+}
+
+/// Reads the circles from JSON written by [`to_json`].
+///
+/// # Errors
+///
+/// Errors if the JSON is invalid, the version is unknown, or a shape is not at a finite point, has
+/// a radius outside the slider's range or a negative or huge stroke width.
+pub fn from_json(json: &str) -> anyhow::Result<Vec<Circle>> {
+    let Version { version } = serde_json::from_str(json)?;
+    if version == 0 || version > VERSION {
+        return Err(anyhow::Error::msg(format!(
+            "document version {version} is not supported, the newest is {VERSION}"
+        )));
+    }
+
+    let document: Document = serde_json::from_str(json)?;
+    document
+        .circles
+        .into_iter()
+        .enumerate()
+        .map(|(index, circle)| {
+            if !circle.x.is_finite() || !circle.y.is_finite() {
+                return Err(anyhow::Error::msg(format!(
+                    "circle {index} at ({}, {}) is not a finite point",
+                    circle.x, circle.y
+                )));
+            }
+            if !RADIUS.contains(&circle.radius) {
+                return Err(anyhow::Error::msg(format!(
+                    "circle {index} has radius {}, which is not between {} and {}",
+                    circle.radius,
+                    RADIUS.start(),
+                    RADIUS.end()
+                )));
+            }
+
+            if !(0.0..=MAX_STROKE_WIDTH).contains(&circle.stroke_width) {
+                return Err(anyhow::Error::msg(format!(
+                    "circle {index} has stroke width {}, which is not between 0 and {MAX_STROKE_WIDTH}",
+                    circle.stroke_width
+                )));
+            }
+
+            Ok(Circle {
+                center: Point::new(circle.x, circle.y),
+                radius: circle.radius,
+                shape: circle.shape,
+                style: Style {
+                    fill: color(circle.fill),
+                    stroke: color(circle.stroke),
+                    stroke_width: circle.stroke_width,
+                },
+            })
+        })
+        .collect()
+}
+
+/// Reads the document at `path`.
+///
+/// # Errors
+///
+/// Errors if the file can't be read or is not a valid document.
+pub fn read(path: &Path) -> anyhow::Result<Vec<Circle>> {
+    from_json(&fs::read_to_string(path)?)
+}
+
+/// Writes the circles to `path`.
+///
+/// # Errors
+///
+/// Errors if the file can't be written.
+pub fn write(path: &Path, circles: &[Circle]) -> anyhow::Result<()> {
+    fs::write(path, to_json(circles)?)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn circle(x: f32, y: f32, radius: f32) -> Circle {
+        Circle {
+            center: Point::new(x, y),
+            radius,
+            ..Circle::default()
+        }
+    }
+
+    fn fields(circles: &[Circle]) -> Vec<(f32, f32, f32)> {
+        circles
+            .iter()
+            .map(|circle| (circle.center.x, circle.center.y, circle.radius))
+            .collect()
+    }
+
+    #[test]
+    fn round_trips() {
+        let circles = [circle(10.0, 20.0, 30.0), circle(400.5, 799.0, 100.0)];
+        let json = to_json(&circles).unwrap();
+
+        assert!(json.contains("\"version\": 2"));
+        assert_eq!(fields(&from_json(&json).unwrap()), fields(&circles));
+    }
+
+    #[test]
+    fn rejects_unknown_versions() {
+        let error = from_json(r#"{ "version": 3, "circles": [] }"#).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "document version 3 is not supported, the newest is 2"
+        );
+
+        assert!(from_json(r#"{ "circles": [] }"#).is_err());
+    }
+
+    #[test]
+    fn rejects_invalid_circles() {
+        let error =
+            from_json(r#"{ "version": 1, "circles": [{ "x": 1e39, "y": 0, "radius": 50 }] }"#)
+                .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "circle 0 at (inf, 0) is not a finite point"
+        );
+
+        let error = from_json(
+            r#"{ "version": 1, "circles": [
+                { "x": 1, "y": 1, "radius": 50 },
+                { "x": 1, "y": 1, "radius": 5 }
+            ] }"#,
+        )
+        .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "circle 1 has radius 5, which is not between 10 and 100"
+        );
+    }
+
+    #[test]
+    fn round_trips_shapes_and_styles() {
+        let circles = [Circle {
+            shape: Shape::Rectangle,
+            style: Style {
+                fill: Color::from_rgba8(255, 0, 0, 0.4),
+                stroke: Color::TRANSPARENT,
+                stroke_width: 4.0,
+            },
+            ..circle(10.0, 20.0, 30.0)
+        }];
+
+        let opened = from_json(&to_json(&circles).unwrap()).unwrap();
+        assert_eq!(opened[0].shape, Shape::Rectangle);
+        assert_eq!(opened[0].style.fill.into_rgba8(), [255, 0, 0, 102]);
+        assert_eq!(opened[0].style.stroke, Color::TRANSPARENT);
+        assert!((opened[0].style.stroke_width - 4.0).abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn reads_version_1_as_default_circles() {
+        let opened =
+            from_json(r#"{ "version": 1, "circles": [{ "x": 1, "y": 2, "radius": 30 }] }"#)
+                .unwrap();
+
+        assert_eq!(opened[0].shape, Shape::Circle);
+        assert_eq!(opened[0].style, Style::default());
+    }
+}
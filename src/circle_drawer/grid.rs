@@ -0,0 +1,179 @@
+//! A uniform grid over the canvas, so hit-testing only looks at the circles near the cursor.
+
+use std::collections::{BTreeMap, HashMap};
+
+use iced::{Point, Rectangle, Size};
+
+use super::{Circle, RADIUS};
+
+/// The side of a grid cell, the largest radius, so a shape covers at most 3 by 3 cells.
+const CELL: f32 = *RADIUS.end();
+
+/// The bounding boxes of the shapes by ID, and the IDs of the shapes each cell overlaps. The
+/// shapes themselves stay with the drawer.
+#[derive(Clone, Debug, Default)]
+pub struct Grid {
+    bounds: HashMap<u64, Rectangle>,
+    cells: HashMap<(i32, i32), Vec<u64>>,
+}
+
+#[allow(clippy::cast_possible_truncation)]
+fn cell(value: f32) -> i32 {
+    (value / CELL).floor() as i32
+}
+
+/// The cells overlapped by `bounds`.
+fn cells(bounds: Rectangle) -> impl Iterator<Item = (i32, i32)> {
+    let xs = cell(bounds.x)..=cell(bounds.x + bounds.width);
+    let ys = cell(bounds.y)..=cell(bounds.y + bounds.height);
+    xs.flat_map(move |x| ys.clone().map(move |y| (x, y)))
+}
+
+impl Grid {
+    /// Indexes `circle` as `id`, replacing where it was before.
+    pub fn set(&mut self, id: u64, circle: &Circle) {
+        self.remove(id);
+
+        let half = circle.half_size();
+        let bounds = Rectangle::new(circle.top_left(), Size::new(2.0 * half.x, 2.0 * half.y));
+        for cell in cells(bounds) {
+            self.cells.entry(cell).or_default().push(id);
+        }
+        self.bounds.insert(id, bounds);
+    }
+
+    /// Takes circle `id` out of the grid.
+    pub fn remove(&mut self, id: u64) {
+        let Some(bounds) = self.bounds.remove(&id) else {
+            return;
+        };
+
+        for cell in cells(bounds) {
+            if let Some(ids) = self.cells.get_mut(&cell) {
+                ids.retain(|other| *other != id);
+                if ids.is_empty() {
+                    self.cells.remove(&cell);
+                }
+            }
+        }
+    }
+
+    /// The ID of the shape in `circles` containing `point` whose center is nearest to it, if any.
+    #[must_use]
+    pub fn hit(&self, point: Point, circles: &BTreeMap<u64, Circle>) -> Option<u64> {
+        self.cells
+            .get(&(cell(point.x), cell(point.y)))?
+            .iter()
+            .filter_map(|id| {
+                let circle = circles.get(id)?;
+                circle
+                    .contains(point)
+                    .then(|| (point.distance(circle.center), *id))
+            })
+            .min_by(|(a, _), (b, _)| a.total_cmp(b))
+            .map(|(_, id)| id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::circle_drawer::style::Shape;
+
+    /// Shapes indexed in a grid, as the drawer keeps them.
+    #[derive(Default)]
+    struct Drawing {
+        grid: Grid,
+        circles: BTreeMap<u64, Circle>,
+    }
+
+    impl Drawing {
+        fn set(&mut self, id: u64, circle: Circle) {
+            self.grid.set(id, &circle);
+            self.circles.insert(id, circle);
+        }
+
+        fn remove(&mut self, id: u64) {
+            self.grid.remove(id);
+            self.circles.remove(&id);
+        }
+
+        fn hit(&self, x: f32, y: f32) -> Option<u64> {
+            self.grid.hit(Point::new(x, y), &self.circles)
+        }
+    }
+
+    fn circle(x: f32, y: f32, radius: f32) -> Circle {
+        Circle {
+            center: Point::new(x, y),
+            radius,
+            ..Circle::default()
+        }
+    }
+
+    #[test]
+    fn hits_the_nearest_containing_circle() {
+        let mut drawing = Drawing::default();
+        drawing.set(0, circle(100.0, 100.0, 50.0));
+        drawing.set(1, circle(160.0, 100.0, 50.0));
+
+        assert_eq!(drawing.hit(120.0, 100.0), Some(0));
+        assert_eq!(drawing.hit(140.0, 100.0), Some(1));
+        assert_eq!(drawing.hit(100.0, 151.0), None);
+        assert_eq!(drawing.hit(-1_000.0, -1_000.0), None);
+    }
+
+    #[test]
+    fn follows_moves_and_removals() {
+        let mut drawing = Drawing::default();
+        drawing.set(0, circle(100.0, 100.0, 50.0));
+        drawing.set(0, circle(700.0, 700.0, 100.0));
+
+        assert_eq!(drawing.hit(100.0, 100.0), None);
+        assert_eq!(drawing.hit(610.0, 700.0), Some(0));
+
+        drawing.remove(0);
+        assert_eq!(drawing.hit(700.0, 700.0), None);
+        assert!(drawing.grid.cells.is_empty());
+        assert!(drawing.grid.bounds.is_empty());
+    }
+
+    #[test]
+    fn finds_circles_spanning_cells() {
+        let mut drawing = Drawing::default();
+        drawing.set(0, circle(199.0, 199.0, 100.0));
+
+        for (x, y) in [
+            (101.0, 199.0),
+            (297.0, 199.0),
+            (199.0, 297.0),
+            (250.0, 250.0),
+        ] {
+            assert_eq!(drawing.hit(x, y), Some(0));
+        }
+    }
+
+    #[test]
+    fn hits_inside_the_shape() {
+        let mut drawing = Drawing::default();
+        drawing.set(
+            0,
+            Circle {
+                shape: Shape::Rectangle,
+                ..circle(400.0, 400.0, 100.0)
+            },
+        );
+        drawing.set(
+            1,
+            Circle {
+                shape: Shape::Ellipse,
+                ..circle(100.0, 100.0, 100.0)
+            },
+        );
+
+        assert_eq!(drawing.hit(495.0, 445.0), Some(0));
+        assert_eq!(drawing.hit(400.0, 455.0), None);
+        assert_eq!(drawing.hit(190.0, 100.0), Some(1));
+        assert_eq!(drawing.hit(100.0, 155.0), None);
+    }
+}
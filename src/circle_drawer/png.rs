@@ -0,0 +1,191 @@
+//! Rendering circle drawings to PNG images on the CPU, without a window or GPU.
+
+use iced::Color;
+use tiny_skia::{FillRule, Paint, Path, PathBuilder, Pixmap, Rect, Stroke, Transform};
+
+use super::{Circle, export_area, style::Shape};
+
+/// The widest and tallest image rendered, in pixels, so a shape panned far away can't exhaust
+/// memory.
+pub const MAX_SIZE: f32 = 16_384.0;
+
+fn path(circle: &Circle) -> Option<Path> {
+    let (center, half) = (circle.center, circle.half_size());
+    match circle.shape {
+        Shape::Circle => PathBuilder::from_circle(center.x, center.y, circle.radius),
+        Shape::Ellipse | Shape::Rectangle => {
+            let top_left = circle.top_left();
+            let rect = Rect::from_xywh(top_left.x, top_left.y, 2.0 * half.x, 2.0 * half.y)?;
+            Some(match circle.shape {
+                Shape::Ellipse => PathBuilder::from_oval(rect)?,
+                _ => PathBuilder::from_rect(rect),
+            })
+        }
+    }
+}
+
+fn paint(color: Color) -> Paint<'static> {
+    let [r, g, b, a] = color.into_rgba8();
+    let mut paint = Paint::default();
+    paint.set_color_rgba8(r, g, b, a);
+    paint.anti_alias = true;
+    paint
+}
+
+fn stroke(width: f32) -> Stroke {
+    Stroke {
+        width,
+        ..Stroke::default()
+    }
+}
+
+/// Draws the shapes in order on a transparent image of their [`export_area`], one pixel per world
+/// unit, as [`Program::draw`](iced::widget::canvas::Program::draw) does: the fill, then the
+/// stroke.
+///
+/// # Errors
+///
+/// Errors if the area is wider or taller than [`MAX_SIZE`].
+pub fn render(circles: &[Circle]) -> anyhow::Result<Pixmap> {
+    let area = export_area(circles);
+    if area.width > MAX_SIZE || area.height > MAX_SIZE {
+        return Err(anyhow::Error::msg(format!(
+            "the drawing is {} by {} pixels, more than {MAX_SIZE} wide or tall",
+            area.width, area.height
+        )));
+    }
+
+    // The area is whole units of at least the canvas size, and within the limit.
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    let mut pixmap = Pixmap::new(area.width as u32, area.height as u32)
+        .ok_or_else(|| anyhow::Error::msg("the image could not be made"))?;
+    let transform = Transform::from_translate(-area.x, -area.y);
+
+    for circle in circles {
+        let Some(path) = path(circle) else {
+            continue;
+        };
+        let style = circle.style;
+        pixmap.fill_path(
+            &path,
+            &paint(style.fill),
+            FillRule::Winding,
+            transform,
+            None,
+        );
+
+        if style.stroke_width > 0.0 {
+            let width = stroke(style.stroke_width);
+            pixmap.stroke_path(&path, &paint(style.stroke), &width, transform, None);
+        }
+    }
+
+    Ok(pixmap)
+}
+
+/// The circles rendered by [`render`], encoded as a PNG image.
+///
+/// # Errors
+///
+/// Errors if the drawing is too large to render or the image can't be encoded.
+pub fn to_png(circles: &[Circle]) -> anyhow::Result<Vec<u8>> {
+    Ok(render(circles)?.encode_png()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use iced::Point;
+
+    use super::*;
+    use crate::circle_drawer::style::Style;
+
+    fn circles() -> [Circle; 3] {
+        [
+            Circle {
+                center: Point::new(100.0, 200.0),
+                radius: 50.0,
+                ..Circle::default()
+            },
+            Circle {
+                center: Point::new(300.5, 400.0),
+                radius: 12.5,
+                shape: Shape::Rectangle,
+                style: Style {
+                    fill: Color::BLACK,
+                    ..Style::default()
+                },
+            },
+            Circle {
+                center: Point::new(600.0, 600.0),
+                radius: 80.0,
+                shape: Shape::Ellipse,
+                style: Style {
+                    fill: Color::from_rgb8(255, 0, 0),
+                    stroke: Color::from_rgb8(0, 0, 255),
+                    stroke_width: 10.0,
+                },
+            },
+        ]
+    }
+
+    #[test]
+    fn grows_to_take_in_far_shapes() {
+        let pixmap = render(&[]).unwrap();
+        assert_eq!((pixmap.width(), pixmap.height()), (800, 800));
+
+        let far = Circle {
+            center: Point::new(-100.0, 1_000.0),
+            radius: 50.0,
+            style: Style {
+                fill: Color::BLACK,
+                ..Style::default()
+            },
+            ..Circle::default()
+        };
+        let pixmap = render(std::slice::from_ref(&far)).unwrap();
+        assert_eq!((pixmap.width(), pixmap.height()), (951, 1051));
+        // The image starts at the world point (-151, 0).
+        let pixel = pixmap.pixel(51, 1_000).unwrap();
+        assert_eq!([pixel.red(), pixel.alpha()], [0, 255]);
+
+        let too_far = Circle {
+            center: Point::new(MAX_SIZE * 2.0, 0.0),
+            ..far
+        };
+        assert!(render(&[too_far]).is_err());
+    }
+
+    #[test]
+    fn fills_circles_like_the_canvas() {
+        let pixmap = render(&circles()).unwrap();
+        let rgba = |x, y| {
+            let pixel = pixmap.pixel(x, y).unwrap();
+            [pixel.red(), pixel.green(), pixel.blue(), pixel.alpha()]
+        };
+
+        assert_eq!(rgba(100, 200), [255, 255, 255, 255]);
+        assert_eq!(rgba(300, 400), [0, 0, 0, 255]);
+        // Nothing is drawn beyond the stroke.
+        assert_eq!(rgba(300, 408), [0, 0, 0, 0]);
+        assert_eq!(rgba(600, 600), [255, 0, 0, 255]);
+        assert_eq!(rgba(680, 600), [0, 0, 255, 255]);
+        assert_eq!(rgba(0, 0), [0, 0, 0, 0]);
+    }
+
+    /// Set `UPDATE_GOLDEN` to rewrite the golden image after an intended change to the drawing.
+    #[test]
+    fn matches_the_golden_image() {
+        let path = concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/golden/circle_drawer.png"
+        );
+        let rendered = render(&circles()).unwrap();
+
+        if std::env::var_os("UPDATE_GOLDEN").is_some() {
+            rendered.save_png(path).unwrap();
+        }
+
+        let golden = Pixmap::load_png(path).expect("the golden image is a PNG");
+        assert!(rendered == golden, "the drawing differs from {path}");
+    }
+}
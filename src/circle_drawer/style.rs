@@ -0,0 +1,117 @@
+//! The shapes the drawer can draw, and the colors and strokes they are drawn with.
+
+use std::fmt;
+
+use iced::Color;
+use serde::{Deserialize, Serialize};
+
+/// The outline drawn around the selected shape, outside its stroke.
+pub const SELECTION: Color = Color::from_rgb(0.2, 0.5, 1.0);
+
+/// The outline drawn around the shape under the cursor.
+pub const HOVER: Color = Color::from_rgba(0.2, 0.5, 1.0, 0.4);
+
+/// How far the selection and hover outlines reach beyond the stroke, on each side.
+pub const OUTLINE_WIDTH: f32 = 3.0;
+
+/// The stroke widths offered in the toolbar.
+pub const STROKE_WIDTHS: [StrokeWidth; 6] = [
+    StrokeWidth(0.0),
+    StrokeWidth(1.0),
+    StrokeWidth(2.0),
+    StrokeWidth(4.0),
+    StrokeWidth(6.0),
+    StrokeWidth(10.0),
+];
+
+/// The colors offered in the toolbar.
+pub const PALETTE: [NamedColor; 10] = [
+    NamedColor::new("White", Color::WHITE),
+    NamedColor::new("Black", Color::BLACK),
+    NamedColor::new("Gray", Color::from_rgb(0.5, 0.5, 0.5)),
+    NamedColor::new("Red", Color::from_rgb(0.9, 0.2, 0.2)),
+    NamedColor::new("Orange", Color::from_rgb(1.0, 0.6, 0.1)),
+    NamedColor::new("Yellow", Color::from_rgb(1.0, 0.9, 0.2)),
+    NamedColor::new("Green", Color::from_rgb(0.2, 0.7, 0.3)),
+    NamedColor::new("Blue", Color::from_rgb(0.2, 0.4, 0.9)),
+    NamedColor::new("Purple", Color::from_rgb(0.6, 0.3, 0.8)),
+    NamedColor::new("None", Color::TRANSPARENT),
+];
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Shape {
+    #[default]
+    Circle,
+    /// Twice as wide as it is tall.
+    Ellipse,
+    /// Twice as wide as it is tall.
+    Rectangle,
+}
+
+impl Shape {
+    pub const ALL: [Shape; 3] = [Shape::Circle, Shape::Ellipse, Shape::Rectangle];
+}
+
+impl fmt::Display for Shape {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Shape::Circle => "Circle",
+            Shape::Ellipse => "Ellipse",
+            Shape::Rectangle => "Rectangle",
+        })
+    }
+}
+
+/// How a shape is filled and stroked.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Style {
+    pub fill: Color,
+    pub stroke: Color,
+    pub stroke_width: f32,
+}
+
+impl Default for Style {
+    fn default() -> Self {
+        Style {
+            fill: Color::WHITE,
+            stroke: Color::BLACK,
+            stroke_width: 1.0,
+        }
+    }
+}
+
+/// A palette entry.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct NamedColor {
+    pub name: &'static str,
+    pub color: Color,
+}
+
+impl NamedColor {
+    const fn new(name: &'static str, color: Color) -> Self {
+        NamedColor { name, color }
+    }
+
+    /// The palette entry for `color`, if it has one.
+    #[must_use]
+    pub fn find(color: Color) -> Option<Self> {
+        PALETTE.into_iter().find(|named| named.color == color)
+    }
+}
+
+impl fmt::Display for NamedColor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name)
+    }
+}
+
+/// A stroke width, shown in pixels.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct StrokeWidth(pub f32);
+
+impl fmt::Display for StrokeWidth {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} px", self.0)
+    }
+}
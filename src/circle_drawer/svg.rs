@@ -0,0 +1,144 @@
+//! Exporting circle drawings as SVG documents.
+
+use std::fmt::Write;
+
+use iced::Color;
+
+use super::{Circle, export_area, style::Shape};
+
+/// `#rrggbb` and the opacity, as SVG spells a color.
+fn svg_color(color: Color) -> (String, f32) {
+    let [r, g, b, _] = color.into_rgba8();
+    (format!("#{r:02x}{g:02x}{b:02x}"), color.a)
+}
+
+/// A paint attribute, like `fill="#ffffff"`, with its opacity if it is not opaque.
+fn paint(attribute: &str, color: Color) -> String {
+    let (color, opacity) = svg_color(color);
+    if opacity < 1.0 {
+        format!(" {attribute}=\"{color}\" {attribute}-opacity=\"{opacity}\"")
+    } else {
+        format!(" {attribute}=\"{color}\"")
+    }
+}
+
+/// The element for the shape's geometry, with `attributes` appended.
+fn element(circle: &Circle, attributes: &str) -> String {
+    let (center, half) = (circle.center, circle.half_size());
+    match circle.shape {
+        Shape::Circle => format!(
+            "  <circle cx=\"{}\" cy=\"{}\" r=\"{}\"{attributes}/>\n",
+            center.x, center.y, circle.radius
+        ),
+        Shape::Ellipse => format!(
+            "  <ellipse cx=\"{}\" cy=\"{}\" rx=\"{}\" ry=\"{}\"{attributes}/>\n",
+            center.x, center.y, half.x, half.y
+        ),
+        Shape::Rectangle => {
+            let top_left = circle.top_left();
+            format!(
+                "  <rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\"{attributes}/>\n",
+                top_left.x,
+                top_left.y,
+                2.0 * half.x,
+                2.0 * half.y
+            )
+        }
+    }
+}
+
+/// Draws the shapes in order over their [`export_area`], as
+/// [`Program::draw`](iced::widget::canvas::Program::draw) does: the fill, then the stroke.
+#[must_use]
+pub fn to_svg(circles: &[Circle]) -> String {
+    let area = export_area(circles);
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" \
+         viewBox=\"{} {} {} {}\">\n",
+        area.width, area.height, area.x, area.y, area.width, area.height
+    );
+
+    for circle in circles {
+        let style = circle.style;
+        let mut attributes = paint("fill", style.fill);
+        if style.stroke_width > 0.0 {
+            attributes.push_str(&paint("stroke", style.stroke));
+            let _ = write!(attributes, " stroke-width=\"{}\"", style.stroke_width);
+        }
+        svg.push_str(&element(circle, &attributes));
+    }
+
+    svg.push_str("</svg>\n");
+    svg
+}
+
+#[cfg(test)]
+mod tests {
+    use iced::Point;
+
+    use super::*;
+    use crate::circle_drawer::style::Style;
+
+    #[test]
+    fn draws_shapes_like_the_canvas() {
+        let circles = [
+            Circle {
+                center: Point::new(100.0, 200.0),
+                radius: 50.0,
+                ..Circle::default()
+            },
+            Circle {
+                center: Point::new(300.5, 400.0),
+                radius: 12.5,
+                shape: Shape::Ellipse,
+                style: Style {
+                    fill: Color::TRANSPARENT,
+                    stroke: Color::from_rgb8(255, 0, 0),
+                    stroke_width: 2.0,
+                },
+            },
+            Circle {
+                center: Point::new(500.0, 500.0),
+                radius: 20.0,
+                shape: Shape::Rectangle,
+                style: Style {
+                    fill: Color::BLACK,
+                    stroke_width: 0.0,
+                    ..Style::default()
+                },
+            },
+        ];
+
+        assert_eq!(
+            to_svg(&circles),
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"800\" height=\"800\" \
+             viewBox=\"0 0 800 800\">\n\
+             \x20 <circle cx=\"100\" cy=\"200\" r=\"50\" fill=\"#ffffff\" stroke=\"#000000\" \
+             stroke-width=\"1\"/>\n\
+             \x20 <ellipse cx=\"300.5\" cy=\"400\" rx=\"12.5\" ry=\"6.25\" fill=\"#000000\" \
+             fill-opacity=\"0\" stroke=\"#ff0000\" stroke-width=\"2\"/>\n\
+             \x20 <rect x=\"480\" y=\"490\" width=\"40\" height=\"20\" fill=\"#000000\"/>\n\
+             </svg>\n"
+        );
+    }
+
+    #[test]
+    fn empty_drawing_keeps_canvas_size() {
+        assert!(to_svg(&[]).contains("width=\"800\" height=\"800\" viewBox=\"0 0 800 800\""));
+    }
+
+    #[test]
+    fn grows_to_take_in_far_shapes() {
+        let circles = [Circle {
+            center: Point::new(-100.0, 1_000.0),
+            radius: 50.0,
+            ..Circle::default()
+        }];
+
+        // The stroke reaches half a unit beyond the radius, rounded out to whole units.
+        assert!(to_svg(&circles).starts_with(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"951\" height=\"1051\" \
+             viewBox=\"-151 0 951 1051\">"
+        ));
+    }
+}
@@ -0,0 +1,54 @@
+//! Counter: a label and a button that increments it.
+
+use iced::widget::{button, row, text};
+use iced::{Alignment, Element, Subscription, keyboard};
+
+use crate::keys;
+
+/// The counter's actions and their default shortcuts.
+pub const KEYS: &keys::Defaults = &[("count", "Space")];
+
+#[derive(Debug, Default)]
+pub struct Counter {
+    value: u32,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum Message {
+    IncrementPressed,
+}
+
+impl Counter {
+    #[must_use]
+    pub fn value(&self) -> u32 {
+        self.value
+    }
+
+    pub fn update(&mut self, message: Message) {
+        match message {
+            Message::IncrementPressed => {
+                self.value += 1;
+            }
+        }
+    }
+
+    /// Counts when the count shortcut is pressed.
+    pub fn subscription(&self) -> Subscription<Message> {
+        keyboard::listen().filter_map(|event| match keys::action("counter", KEYS, &event)? {
+            "count" => Some(Message::IncrementPressed),
+            _ => None,
+        })
+    }
+
+    #[must_use]
+    pub fn view(&self) -> Element<'_, Message> {
+        row![
+            text(self.value).size(50),
+            button("Count").on_press(Message::IncrementPressed),
+        ]
+        .padding(20)
+        .spacing(50)
+        .align_y(Alignment::Center)
+        .into()
+    }
+}
@@ -0,0 +1,562 @@
+//! CRUD: create, read, update and delete names, filtered by a prefix.
+
+pub mod contacts;
+pub mod storage;
+
+use std::{fmt, path::Path};
+
+use iced::{
+    Alignment, Element, Font, Subscription, keyboard,
+    widget::{Column, button, checkbox, column, row, scrollable, text, text_input},
+};
+use iced_aw::{SelectionList, style};
+use serde::{Deserialize, Serialize};
+
+use crate::keys;
+use storage::Storage;
+
+const SPACING: u32 = 10;
+
+/// The CRUD's actions and their default shortcuts.
+pub const KEYS: &keys::Defaults = &[("create", "Ctrl+N"), ("delete", "Delete")];
+
+/// A record in the database. The `id` stays the same when the names change.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Deserialize, Serialize)]
+pub struct Person {
+    pub id: u64,
+    pub name: String,
+    pub surname: String,
+}
+
+impl Person {
+    /// Whether the "Surname, Name" form starts with `prefix`.
+    #[must_use]
+    pub fn starts_with(&self, prefix: &str, ignore_case: bool) -> bool {
+        let string = self.to_string();
+
+        if ignore_case {
+            string.to_lowercase().starts_with(&prefix.to_lowercase())
+        } else {
+            string.starts_with(prefix)
+        }
+    }
+}
+
+impl fmt::Display for Person {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}, {}", self.surname, self.name)
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct Crud {
+    filter_prefix: String,
+    ignore_case: bool,
+    selected: Option<u64>,
+    name: String,
+    sur_name: String,
+    people: Vec<Person>,
+    display_people: Vec<Person>,
+    next_id: u64,
+    storage: Option<Storage>,
+    error: Option<String>,
+    file: String,
+    status: Option<String>,
+    import_errors: Vec<contacts::LineError>,
+}
+
+#[derive(Clone, Debug)]
+pub enum Message {
+    FilterPrefixChanged(String),
+    IgnoreCaseToggled(bool),
+    PersonSelected(usize, Person),
+    NameChanged(String),
+    SurnameChanged(String),
+    CreatePressed,
+    UpdatePressed,
+    DeletePressed,
+    FileChanged(String),
+    ImportPressed,
+    ExportPressed,
+}
+
+impl Crud {
+    /// Loads the names saved in the user's data directory, and saves there after every change.
+    #[must_use]
+    pub fn load() -> Self {
+        match Storage::in_data_dir() {
+            Some(storage) => Crud::with_storage(storage),
+            None => Crud {
+                error: Some("No data directory was found, so names will not be saved.".to_string()),
+                ..Crud::default()
+            },
+        }
+    }
+
+    /// Loads the names from `storage`, and saves there after every change.
+    ///
+    /// An unreadable file is backed up and replaced by an empty list, with the error shown.
+    #[must_use]
+    pub fn with_storage(storage: Storage) -> Self {
+        let mut crud = Crud::default();
+
+        match storage.load() {
+            Ok(people) => {
+                crud.next_id = people.iter().map(|person| person.id + 1).max().unwrap_or(0);
+                crud.people = people;
+            }
+            Err(error) => {
+                let path = storage.path().display();
+                crud.error = Some(match storage.back_up() {
+                    Ok(backup) => format!(
+                        "Could not load {path}: {error}. It was moved to {}.",
+                        backup.display()
+                    ),
+                    Err(backup_error) => {
+                        format!(
+                            "Could not load {path}: {error}. Could not back it up: {backup_error}."
+                        )
+                    }
+                });
+            }
+        }
+
+        crud.storage = Some(storage);
+        crud.filter();
+        crud
+    }
+
+    /// The error from loading or saving, if any.
+    #[must_use]
+    pub fn error(&self) -> Option<&str> {
+        self.error.as_deref()
+    }
+
+    #[must_use]
+    pub fn people(&self) -> &[Person] {
+        &self.people
+    }
+
+    /// The people matching the filter prefix, as shown in the list.
+    #[must_use]
+    pub fn display_people(&self) -> &[Person] {
+        &self.display_people
+    }
+
+    /// The ID of the selected person, if any.
+    #[must_use]
+    pub fn selected(&self) -> Option<u64> {
+        self.selected
+    }
+
+    /// The result of the last import or export, if any.
+    #[must_use]
+    pub fn status(&self) -> Option<&str> {
+        self.status.as_deref()
+    }
+
+    /// Adds the people in the CSV or vCard file at `path`, giving each a new ID, and saves.
+    ///
+    /// # Errors
+    ///
+    /// Errors if the file can't be read or its format is unknown. Invalid lines are returned in
+    /// [`contacts::Import::errors`] instead, and the valid ones are still added.
+    pub fn import(&mut self, path: &Path) -> anyhow::Result<contacts::Import> {
+        let import = contacts::read(path)?;
+        self.add_contacts(&import.contacts);
+        Ok(import)
+    }
+
+    /// Adds `contacts`, giving each a new ID, and saves.
+    fn add_contacts(&mut self, contacts: &[contacts::Contact]) {
+        for contact in contacts {
+            self.people.push(Person {
+                id: self.next_id,
+                name: contact.name.clone(),
+                surname: contact.surname.clone(),
+            });
+            self.next_id += 1;
+        }
+
+        if !contacts.is_empty() {
+            self.save();
+        }
+        self.filter();
+    }
+
+    /// Writes every person to the CSV or vCard file at `path`.
+    ///
+    /// # Errors
+    ///
+    /// Errors if the file can't be written or its format is unknown.
+    pub fn export(&self, path: &Path) -> anyhow::Result<()> {
+        contacts::write(path, &self.people)
+    }
+
+    pub fn update(&mut self, message: Message) {
+        match message {
+            Message::FilterPrefixChanged(prefix) => {
+                self.filter_prefix = prefix;
+                self.selected = None;
+            }
+            Message::IgnoreCaseToggled(ignore_case) => {
+                self.ignore_case = ignore_case;
+            }
+            Message::PersonSelected(_index, person) => {
+                self.selected = Some(person.id);
+            }
+            Message::NameChanged(name) => {
+                self.name = name;
+            }
+            Message::SurnameChanged(name) => {
+                self.sur_name = name;
+            }
+            // The shortcut can't be disabled like the button, so it checks the names itself.
+            Message::CreatePressed if self.name.is_empty() || self.sur_name.is_empty() => {}
+            Message::CreatePressed => {
+                self.people.push(Person {
+                    id: self.next_id,
+                    name: self.name.clone(),
+                    surname: self.sur_name.clone(),
+                });
+                self.next_id += 1;
+                self.selected = None;
+                self.save();
+            }
+            Message::UpdatePressed => {
+                if let Some(person) = self
+                    .people
+                    .iter_mut()
+                    .find(|person| Some(person.id) == self.selected)
+                {
+                    person.name.clone_from(&self.name);
+                    person.surname.clone_from(&self.sur_name);
+                    self.save();
+                }
+            }
+            Message::DeletePressed => {
+                if let Some(id) = self.selected.take() {
+                    self.people.retain(|person| person.id != id);
+                    self.save();
+                }
+            }
+            Message::FileChanged(file) => {
+                self.file = file;
+            }
+            Message::ImportPressed => {
+                self.import_errors.clear();
+                // Read before adding, so the file name is only borrowed.
+                self.status = Some(match contacts::read(Path::new(&self.file)) {
+                    Ok(import) => {
+                        self.add_contacts(&import.contacts);
+                        let status = format!(
+                            "Imported {} people from {}, skipped {} lines.",
+                            import.contacts.len(),
+                            self.file,
+                            import.errors.len()
+                        );
+                        self.import_errors = import.errors;
+                        status
+                    }
+                    Err(error) => format!("Could not import {}: {error}", self.file),
+                });
+            }
+            Message::ExportPressed => {
+                self.import_errors.clear();
+                self.status = Some(match self.export(Path::new(&self.file)) {
+                    Ok(()) => format!("Exported {} people to {}.", self.people.len(), self.file),
+                    Err(error) => format!("Could not export {}: {error}", self.file),
+                });
+            }
+        }
+
+        self.filter();
+    }
+
+    fn filter(&mut self) {
+        self.display_people = self
+            .people
+            .iter()
+            .filter(|person| person.starts_with(&self.filter_prefix, self.ignore_case))
+            .cloned()
+            .collect();
+
+        // Only what is shown can be selected.
+        if !self
+            .display_people
+            .iter()
+            .any(|person| Some(person.id) == self.selected)
+        {
+            self.selected = None;
+        }
+    }
+
+    fn save(&mut self) {
+        if let Some(storage) = &self.storage {
+            self.error = storage
+                .save(&self.people)
+                .err()
+                .map(|error| format!("Could not save {}: {error}", storage.path().display()));
+        }
+    }
+
+    /// The file name, with the Import and Export buttons.
+    fn file_row(&self) -> Element<'_, Message> {
+        let file = if self.file.is_empty() {
+            (button("Import"), button("Export"))
+        } else {
+            (
+                button("Import").on_press(Message::ImportPressed),
+                button("Export").on_press(Message::ExportPressed),
+            )
+        };
+        row![
+            "File:",
+            text_input("contacts.csv or contacts.vcf", &self.file)
+                .id("file")
+                .on_input(Message::FileChanged)
+                .width(300),
+            file.0,
+            file.1,
+        ]
+        .padding(10)
+        .spacing(SPACING)
+        .align_y(Alignment::Center)
+        .into()
+    }
+
+    /// Creates or deletes when their shortcuts are pressed. Delete only reaches here when no text
+    /// input is focused.
+    pub fn subscription(&self) -> Subscription<Message> {
+        keyboard::listen().filter_map(|event| match keys::action("crud", KEYS, &event)? {
+            "create" => Some(Message::CreatePressed),
+            "delete" => Some(Message::DeletePressed),
+            _ => None,
+        })
+    }
+
+    #[must_use]
+    pub fn view(&self) -> Element<'_, Message> {
+        let filter_prefix = row![
+            "Filter prefix:",
+            text_input("", &self.filter_prefix)
+                .id("filter prefix")
+                .on_input(Message::FilterPrefixChanged)
+                .width(190),
+            checkbox(self.ignore_case)
+                .label("Ignore case")
+                .on_toggle(Message::IgnoreCaseToggled),
+        ]
+        .spacing(SPACING)
+        .padding(10)
+        .align_y(Alignment::Start);
+
+        let selection_list = row![
+            SelectionList::new_with(
+                &self.display_people[..],
+                Message::PersonSelected,
+                12.0,
+                5.0,
+                style::selection_list::primary,
+                self.selected.and_then(|id| {
+                    self.display_people
+                        .iter()
+                        .position(|person| person.id == id)
+                }),
+                Font::default(),
+            )
+            .width(iced::Length::Fixed(300.0))
+            .height(iced::Length::Fixed(200.0))
+        ]
+        .padding(10);
+
+        let name = row![
+            "Name:",
+            text_input("", &self.name)
+                .id("name")
+                .on_input(Message::NameChanged)
+        ]
+        .spacing(SPACING);
+
+        let surname = row![
+            "Surname:",
+            text_input("", &self.sur_name)
+                .id("surname")
+                .on_input(Message::SurnameChanged),
+        ]
+        .spacing(SPACING);
+
+        let enter_name = column![name, surname].padding(10).spacing(10);
+
+        let names_box = row![selection_list, enter_name];
+
+        let create = button("Create");
+        let create = if self.sur_name.is_empty() || self.name.is_empty() {
+            create
+        } else {
+            create.on_press(Message::CreatePressed)
+        };
+
+        let update = button("Update");
+        let update = if self.sur_name.is_empty() || self.name.is_empty() || self.selected.is_none()
+        {
+            update
+        } else {
+            update.on_press(Message::UpdatePressed)
+        };
+
+        let delete = button("Delete");
+        let delete = if self.selected.is_none() {
+            delete
+        } else {
+            delete.on_press(Message::DeletePressed)
+        };
+
+        let buttons = row![create, update, delete]
+            .padding(10)
+            .spacing(10)
+            .align_y(Alignment::Start);
+
+        let file = self.file_row();
+
+        let mut column = column![filter_prefix, names_box, buttons, file].align_x(Alignment::Start);
+
+        if let Some(status) = &self.status {
+            column = column.push(text(status).width(560));
+        }
+
+        if !self.import_errors.is_empty() {
+            let errors = self
+                .import_errors
+                .iter()
+                .map(|error| text(error.to_string()).style(text::danger).into());
+            column = column.push(scrollable(Column::with_children(errors).width(560)).height(80));
+        }
+
+        if let Some(error) = &self.error {
+            column = column.push(text(error).style(text::danger).width(560));
+        }
+
+        column.into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create(crud: &mut Crud, name: &str, surname: &str) -> Person {
+        crud.update(Message::NameChanged(name.to_string()));
+        crud.update(Message::SurnameChanged(surname.to_string()));
+        crud.update(Message::CreatePressed);
+        crud.people().last().unwrap().clone()
+    }
+
+    #[test]
+    fn filter_prefix_longer_than_names_does_not_panic() {
+        let mut crud = Crud::default();
+        create(&mut crud, "Jo", "Do");
+        crud.update(Message::FilterPrefixChanged("Do, Jo and more".to_string()));
+
+        assert!(crud.display_people().is_empty());
+    }
+
+    #[test]
+    fn filter_prefix_inside_a_character_does_not_panic() {
+        let mut crud = Crud::default();
+        let zoe = create(&mut crud, "Zoë", "Ünal");
+        create(&mut crud, "Max", "Mustermann");
+
+        // "Ü" is two bytes long, so a one byte prefix would slice through it.
+        crud.update(Message::FilterPrefixChanged("U".to_string()));
+        assert!(crud.display_people().is_empty());
+
+        crud.update(Message::FilterPrefixChanged("Ünal, Zo".to_string()));
+        assert_eq!(crud.display_people(), [zoe]);
+    }
+
+    #[test]
+    fn filter_drives_the_list_and_selection() {
+        let mut crud = Crud::default();
+        create(&mut crud, "John", "Doe");
+        let max = create(&mut crud, "Max", "Mustermann");
+
+        crud.update(Message::FilterPrefixChanged("Mu".to_string()));
+        assert_eq!(crud.display_people(), std::slice::from_ref(&max));
+
+        // The first row of the filtered list is Max, not John.
+        crud.update(Message::PersonSelected(0, max.clone()));
+        crud.update(Message::DeletePressed);
+        assert_eq!(crud.people()[0].to_string(), "Doe, John");
+        assert_eq!(crud.people().len(), 1);
+    }
+
+    #[test]
+    fn filter_can_ignore_case() {
+        let mut crud = Crud::default();
+        let zoe = create(&mut crud, "Zoë", "Ünal");
+
+        crud.update(Message::FilterPrefixChanged("üNAL".to_string()));
+        assert!(crud.display_people().is_empty());
+
+        crud.update(Message::IgnoreCaseToggled(true));
+        assert_eq!(crud.display_people(), [zoe]);
+    }
+
+    #[test]
+    fn hidden_people_are_deselected() {
+        let mut crud = Crud::default();
+        let john = create(&mut crud, "John", "Doe");
+        crud.update(Message::FilterPrefixChanged("Doe".to_string()));
+        crud.update(Message::PersonSelected(0, john));
+
+        crud.update(Message::SurnameChanged("Smith".to_string()));
+        crud.update(Message::UpdatePressed);
+
+        assert!(crud.display_people().is_empty());
+        assert_eq!(crud.selected(), None);
+    }
+
+    #[test]
+    fn create_needs_both_names() {
+        let mut crud = Crud::default();
+        crud.update(Message::NameChanged("John".to_string()));
+        crud.update(Message::CreatePressed);
+        assert!(crud.people().is_empty());
+    }
+
+    #[test]
+    fn duplicates_are_updated_and_deleted_by_id() {
+        let mut crud = Crud::default();
+        let first = create(&mut crud, "John", "Doe");
+        let second = create(&mut crud, "John", "Doe");
+        assert_ne!(first.id, second.id);
+
+        crud.update(Message::PersonSelected(1, second.clone()));
+        crud.update(Message::NameChanged("Jane".to_string()));
+        crud.update(Message::UpdatePressed);
+        assert_eq!(crud.people()[0], first);
+        assert_eq!(crud.people()[1].to_string(), "Doe, Jane");
+
+        crud.update(Message::PersonSelected(1, crud.people()[1].clone()));
+        crud.update(Message::DeletePressed);
+        assert_eq!(crud.people(), [first]);
+        assert_eq!(crud.selected(), None);
+    }
+
+    #[test]
+    fn import_adds_valid_rows_with_new_ids() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("import.csv");
+        std::fs::write(&path, "name,surname\nJane,Roe\nMax\n").unwrap();
+
+        let mut crud = Crud::default();
+        let john = create(&mut crud, "John", "Doe");
+        let import = crud.import(&path).unwrap();
+
+        assert_eq!(import.errors.len(), 1);
+        assert_eq!(crud.people()[1].to_string(), "Roe, Jane");
+        assert_ne!(crud.people()[1].id, john.id);
+        assert_eq!(crud.display_people().len(), 2);
+    }
+}
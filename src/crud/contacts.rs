@@ -0,0 +1,489 @@
+//! Reading and writing people as CSV (`name,surname` with a header) and vCard 3.0 or 4.0.
+
+use std::{fmt, fs, path::Path};
+
+use super::Person;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+    Csv,
+    VCard,
+}
+
+impl Format {
+    /// Picks the format from the file extension: `.csv`, or `.vcf` or `.vcard`.
+    ///
+    /// # Errors
+    ///
+    /// Errors if the extension is not one of those.
+    pub fn from_path(path: &Path) -> anyhow::Result<Self> {
+        let extension = path
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .map(str::to_ascii_lowercase);
+
+        match extension.as_deref() {
+            Some("csv") => Ok(Format::Csv),
+            Some("vcf" | "vcard") => Ok(Format::VCard),
+            _ => Err(anyhow::Error::msg(format!(
+                "{} is not a .csv, .vcf or .vcard file",
+                path.display()
+            ))),
+        }
+    }
+}
+
+/// A name read from a file, before it gets an ID.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Contact {
+    pub name: String,
+    pub surname: String,
+}
+
+/// A line that could not be imported. Lines are numbered from 1.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LineError {
+    pub line: u64,
+    pub message: String,
+}
+
+impl LineError {
+    fn new(line: u64, message: impl Into<String>) -> Self {
+        LineError {
+            line,
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for LineError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+/// The contacts that were read, and the lines that were skipped.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Import {
+    pub contacts: Vec<Contact>,
+    pub errors: Vec<LineError>,
+}
+
+impl Import {
+    fn push(&mut self, line: u64, name: String, surname: String) {
+        if name.is_empty() || surname.is_empty() {
+            self.errors
+                .push(LineError::new(line, "both a name and a surname are needed"));
+        } else {
+            self.contacts.push(Contact { name, surname });
+        }
+    }
+}
+
+/// Reads the file at `path` in the format given by its extension.
+///
+/// # Errors
+///
+/// Errors if the format is unknown or the file can't be read. Invalid lines are returned in
+/// [`Import::errors`] instead.
+pub fn read(path: &Path) -> anyhow::Result<Import> {
+    let format = Format::from_path(path)?;
+    let text = fs::read_to_string(path)?;
+
+    Ok(match format {
+        Format::Csv => parse_csv(&text),
+        Format::VCard => parse_vcard(&text),
+    })
+}
+
+/// Writes `people` to `path` in the format given by its extension.
+///
+/// # Errors
+///
+/// Errors if the format is unknown or the file can't be written.
+pub fn write(path: &Path, people: &[Person]) -> anyhow::Result<()> {
+    let text = match Format::from_path(path)? {
+        Format::Csv => to_csv(people)?,
+        Format::VCard => to_vcard(people),
+    };
+
+    fs::write(path, text)?;
+    Ok(())
+}
+
+#[must_use]
+pub fn parse_csv(text: &str) -> Import {
+    let mut import = Import::default();
+    let mut reader = csv::ReaderBuilder::new()
+        .flexible(true)
+        .trim(csv::Trim::All)
+        .from_reader(text.as_bytes());
+
+    let headers = match reader.headers() {
+        Ok(headers) => headers.clone(),
+        Err(error) => {
+            import.errors.push(LineError::new(1, error.to_string()));
+            return import;
+        }
+    };
+
+    let column = |wanted: &str| {
+        headers
+            .iter()
+            .position(|header| header.eq_ignore_ascii_case(wanted))
+    };
+    let (Some(name_column), Some(surname_column)) = (column("name"), column("surname")) else {
+        import.errors.push(LineError::new(
+            1,
+            "the header needs a name and a surname column",
+        ));
+        return import;
+    };
+
+    for record in reader.records() {
+        match record {
+            Ok(record) => {
+                let line = record.position().map_or(0, csv::Position::line);
+                match (record.get(name_column), record.get(surname_column)) {
+                    (Some(name), Some(surname)) => {
+                        import.push(line, name.to_string(), surname.to_string());
+                    }
+                    _ => import.errors.push(LineError::new(
+                        line,
+                        format!("expected {} fields, found {}", headers.len(), record.len()),
+                    )),
+                }
+            }
+            Err(error) => {
+                let line = error.position().map_or(0, csv::Position::line);
+                import.errors.push(LineError::new(line, error.to_string()));
+            }
+        }
+    }
+
+    import
+}
+
+/// Writes a `name,surname` header and one row per person.
+///
+/// # Errors
+///
+/// Errors if the CSV writer fails.
+pub fn to_csv(people: &[Person]) -> anyhow::Result<String> {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    writer.write_record(["name", "surname"])?;
+    for person in people {
+        writer.write_record([&person.name, &person.surname])?;
+    }
+
+    Ok(String::from_utf8(writer.into_inner()?)?)
+}
+
+/// A vCard being read, and the line of its `BEGIN:VCARD`.
+struct Card {
+    line: u64,
+    names: Option<(String, String)>,
+    version_error: Option<LineError>,
+}
+
+#[must_use]
+pub fn parse_vcard(text: &str) -> Import {
+    let mut import = Import::default();
+    let mut card: Option<Card> = None;
+
+    for (line, content) in unfold(text) {
+        if content.trim().is_empty() {
+            continue;
+        }
+
+        let Some((property, value)) = content.split_once(':') else {
+            import
+                .errors
+                .push(LineError::new(line, "expected a property and a value"));
+            continue;
+        };
+
+        // Drop the parameters, like `;CHARSET=UTF-8`, and the group, like `item1.`.
+        let name = property.split(';').next().unwrap_or_default();
+        let name = name
+            .rsplit('.')
+            .next()
+            .unwrap_or_default()
+            .to_ascii_uppercase();
+
+        match (name.as_str(), &mut card) {
+            ("BEGIN", _) if value.eq_ignore_ascii_case("VCARD") => {
+                if let Some(card) = &card {
+                    import
+                        .errors
+                        .push(LineError::new(card.line, "the card is not closed"));
+                }
+
+                card = Some(Card {
+                    line,
+                    names: None,
+                    version_error: None,
+                });
+            }
+            ("END", Some(_)) if value.eq_ignore_ascii_case("VCARD") => {
+                let Some(card) = card.take() else {
+                    continue;
+                };
+
+                match (card.version_error, card.names) {
+                    (Some(error), _) => import.errors.push(error),
+                    (None, Some((name, surname))) => import.push(card.line, name, surname),
+                    (None, None) => import
+                        .errors
+                        .push(LineError::new(card.line, "the card has no N field")),
+                }
+            }
+            ("VERSION", Some(card)) => {
+                if value.trim() != "3.0" && value.trim() != "4.0" {
+                    card.version_error = Some(LineError::new(
+                        line,
+                        format!("vCard version {} is not supported", value.trim()),
+                    ));
+                }
+            }
+            ("N", Some(card)) => {
+                let mut components = split_components(value).into_iter();
+                let surname = components.next().unwrap_or_default();
+                let name = components.next().unwrap_or_default();
+                card.names = Some((name, surname));
+            }
+            (_, Some(_)) => {}
+            (_, None) => import
+                .errors
+                .push(LineError::new(line, "expected BEGIN:VCARD")),
+        }
+    }
+
+    if let Some(card) = card {
+        import
+            .errors
+            .push(LineError::new(card.line, "the card is not closed"));
+    }
+
+    import
+}
+
+/// Joins folded lines, which continue with a leading space or tab, keeping the first line number.
+fn unfold(text: &str) -> Vec<(u64, String)> {
+    let mut lines: Vec<(u64, String)> = Vec::new();
+
+    for (line, content) in (1..).zip(text.lines()) {
+        if let Some(rest) = content
+            .strip_prefix(' ')
+            .or_else(|| content.strip_prefix('\t'))
+            && let Some((_, previous)) = lines.last_mut()
+        {
+            previous.push_str(rest);
+        } else {
+            lines.push((line, content.to_string()));
+        }
+    }
+
+    lines
+}
+
+/// Splits a structured value on unescaped `;`, unescaping each component.
+fn split_components(value: &str) -> Vec<String> {
+    let mut components = vec![String::new()];
+    let mut chars = value.chars();
+
+    while let Some(char) = chars.next() {
+        let component = components.last_mut().expect("there is always a component");
+        match char {
+            '\\' => match chars.next() {
+                Some('n' | 'N') => component.push('\n'),
+                Some(escaped) => component.push(escaped),
+                None => {}
+            },
+            ';' => components.push(String::new()),
+            char => component.push(char),
+        }
+    }
+
+    components
+        .into_iter()
+        .map(|component| component.trim().to_string())
+        .collect()
+}
+
+fn escape(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for char in value.chars() {
+        match char {
+            '\\' | ',' | ';' => {
+                escaped.push('\\');
+                escaped.push(char);
+            }
+            '\n' => escaped.push_str("\\n"),
+            char => escaped.push(char),
+        }
+    }
+    escaped
+}
+
+/// Writes one vCard 4.0 per person.
+#[must_use]
+pub fn to_vcard(people: &[Person]) -> String {
+    let mut text = String::new();
+    for person in people {
+        let name = escape(&person.name);
+        let surname = escape(&person.surname);
+
+        text.push_str("BEGIN:VCARD\r\nVERSION:4.0\r\n");
+        text.push_str(&["N:", &surname, ";", &name, ";;;\r\n"].concat());
+        text.push_str(&["FN:", &name, " ", &surname, "\r\n"].concat());
+        text.push_str("END:VCARD\r\n");
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn contact(name: &str, surname: &str) -> Contact {
+        Contact {
+            name: name.to_string(),
+            surname: surname.to_string(),
+        }
+    }
+
+    fn person(id: u64, name: &str, surname: &str) -> Person {
+        Person {
+            id,
+            name: name.to_string(),
+            surname: surname.to_string(),
+        }
+    }
+
+    #[test]
+    fn picks_format_from_extension() {
+        assert_eq!(Format::from_path(Path::new("a.CSV")).unwrap(), Format::Csv);
+        assert_eq!(
+            Format::from_path(Path::new("a.vcf")).unwrap(),
+            Format::VCard
+        );
+        assert!(Format::from_path(Path::new("a.txt")).is_err());
+    }
+
+    #[test]
+    fn reads_csv() {
+        let import = parse_csv("Surname,Name\nDoe,John\n\n\"Smith, Jr\",Jane\n");
+
+        assert_eq!(
+            import.contacts,
+            [contact("John", "Doe"), contact("Jane", "Smith, Jr")]
+        );
+        assert!(import.errors.is_empty());
+    }
+
+    #[test]
+    fn reports_csv_errors_per_line() {
+        let import = parse_csv("name,surname\nJohn,Doe\nMax\n,Smith\nJane,Roe\n");
+
+        assert_eq!(
+            import.contacts,
+            [contact("John", "Doe"), contact("Jane", "Roe")]
+        );
+        assert_eq!(
+            import.errors,
+            [
+                LineError::new(3, "expected 2 fields, found 1"),
+                LineError::new(4, "both a name and a surname are needed"),
+            ]
+        );
+    }
+
+    #[test]
+    fn requires_csv_header() {
+        let import = parse_csv("John,Doe\n");
+
+        assert!(import.contacts.is_empty());
+        assert_eq!(
+            import.errors,
+            [LineError::new(
+                1,
+                "the header needs a name and a surname column"
+            )]
+        );
+    }
+
+    #[test]
+    fn round_trips_csv() {
+        let people = [person(0, "John", "Doe"), person(1, "Jane", "Smith, Jr")];
+        let import = parse_csv(&to_csv(&people).unwrap());
+
+        assert_eq!(
+            import.contacts,
+            [contact("John", "Doe"), contact("Jane", "Smith, Jr")]
+        );
+    }
+
+    #[test]
+    fn reads_vcards() {
+        let import = parse_vcard(
+            "BEGIN:VCARD\r\n\
+             VERSION:3.0\r\n\
+             N;CHARSET=UTF-8:Doe;John;;;\r\n\
+             FN:John Doe\r\n\
+             END:VCARD\r\n\
+             BEGIN:VCARD\r\n\
+             VERSION:4.0\r\n\
+             item1.N:Smith\\, J\r\n \
+             r;Jane\r\n\
+             END:VCARD\r\n",
+        );
+
+        assert_eq!(
+            import.contacts,
+            [contact("John", "Doe"), contact("Jane", "Smith, Jr")]
+        );
+        assert!(import.errors.is_empty());
+    }
+
+    #[test]
+    fn reports_vcard_errors_per_line() {
+        let import = parse_vcard(
+            "FN:Stray\n\
+             BEGIN:VCARD\n\
+             VERSION:2.1\n\
+             N:Old;Card\n\
+             END:VCARD\n\
+             BEGIN:VCARD\n\
+             FN:No Name\n\
+             END:VCARD\n\
+             BEGIN:VCARD\n\
+             N:Doe;John\n\
+             END:VCARD\n\
+             BEGIN:VCARD\n\
+             N:Open;Card\n",
+        );
+
+        assert_eq!(import.contacts, [contact("John", "Doe")]);
+        assert_eq!(
+            import.errors,
+            [
+                LineError::new(1, "expected BEGIN:VCARD"),
+                LineError::new(3, "vCard version 2.1 is not supported"),
+                LineError::new(6, "the card has no N field"),
+                LineError::new(12, "the card is not closed"),
+            ]
+        );
+    }
+
+    #[test]
+    fn round_trips_vcards() {
+        let people = [person(0, "John", "Doe"), person(1, "Jane", "Smith; Jr")];
+        let import = parse_vcard(&to_vcard(&people));
+
+        assert_eq!(
+            import.contacts,
+            [contact("John", "Doe"), contact("Jane", "Smith; Jr")]
+        );
+    }
+}
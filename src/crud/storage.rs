@@ -0,0 +1,171 @@
+//! Saving the CRUD people to a JSON file.
+
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+
+use super::Person;
+
+const FILE_NAME: &str = "crud.json";
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+struct Database {
+    #[serde(default)]
+    people: Vec<Person>,
+    /// "Surname, Name" strings written before people had IDs.
+    #[serde(default, skip_serializing)]
+    names: Vec<String>,
+}
+
+/// A JSON file holding the people.
+#[derive(Clone, Debug)]
+pub struct Storage {
+    path: PathBuf,
+}
+
+impl Storage {
+    #[must_use]
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Storage { path: path.into() }
+    }
+
+    /// The file in the user's data directory, e.g. `~/.local/share/iced_7guis/crud.json`.
+    #[must_use]
+    pub fn in_data_dir() -> Option<Self> {
+        dirs::data_dir().map(|dir| Storage::new(dir.join(env!("CARGO_PKG_NAME")).join(FILE_NAME)))
+    }
+
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Reads the people. A missing file holds no people.
+    ///
+    /// # Errors
+    ///
+    /// Errors if the file can't be read or is not valid JSON.
+    pub fn load(&self) -> anyhow::Result<Vec<Person>> {
+        let json = match fs::read_to_string(&self.path) {
+            Ok(json) => json,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(error) => return Err(error.into()),
+        };
+
+        let mut database: Database = serde_json::from_str(&json)?;
+
+        let first_id = database
+            .people
+            .iter()
+            .map(|person| person.id + 1)
+            .max()
+            .unwrap_or(0);
+        for (id, name) in (first_id..).zip(database.names) {
+            let (surname, name) = name.split_once(", ").unwrap_or(("", &name));
+            database.people.push(Person {
+                id,
+                name: name.to_string(),
+                surname: surname.to_string(),
+            });
+        }
+
+        Ok(database.people)
+    }
+
+    /// Writes the people, creating the directory if needed.
+    ///
+    /// # Errors
+    ///
+    /// Errors if the file can't be written.
+    pub fn save(&self, people: &[Person]) -> anyhow::Result<()> {
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+
+        let json = serde_json::to_string_pretty(&Database {
+            people: people.to_vec(),
+            names: Vec::new(),
+        })?;
+
+        // Write to a temporary file first, so a crash can't leave a half written database.
+        let temporary = self.path.with_extension("json.tmp");
+        fs::write(&temporary, json)?;
+        fs::rename(&temporary, &self.path)?;
+
+        Ok(())
+    }
+
+    /// Moves an unreadable file out of the way, so saving doesn't overwrite it.
+    ///
+    /// # Errors
+    ///
+    /// Errors if the file can't be renamed.
+    pub fn back_up(&self) -> anyhow::Result<PathBuf> {
+        let backup = self.path.with_extension("json.bak");
+        fs::rename(&self.path, &backup)?;
+        Ok(backup)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::TempDir;
+
+    use super::*;
+
+    /// Storage in a new directory, which is removed when the [`TempDir`] is dropped.
+    fn storage() -> (TempDir, Storage) {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = Storage::new(dir.path().join(FILE_NAME));
+        (dir, storage)
+    }
+
+    #[test]
+    fn missing_file_is_empty() {
+        let (_dir, storage) = storage();
+        assert!(storage.load().unwrap().is_empty());
+    }
+
+    fn person(id: u64, name: &str, surname: &str) -> Person {
+        Person {
+            id,
+            name: name.to_string(),
+            surname: surname.to_string(),
+        }
+    }
+
+    #[test]
+    fn round_trips() {
+        let (_dir, storage) = storage();
+        let people = [person(0, "John", "Doe"), person(3, "John", "Doe")];
+
+        storage.save(&people).unwrap();
+        assert_eq!(storage.load().unwrap(), people);
+    }
+
+    #[test]
+    fn reads_names_without_ids() {
+        let (_dir, storage) = storage();
+        fs::write(storage.path(), r#"{ "names": ["Doe, John", "Max"] }"#).unwrap();
+
+        assert_eq!(
+            storage.load().unwrap(),
+            [person(0, "John", "Doe"), person(1, "Max", "")]
+        );
+    }
+
+    #[test]
+    fn backs_up_corrupt_files() {
+        let (_dir, storage) = storage();
+        fs::write(storage.path(), "{ not json").unwrap();
+
+        assert!(storage.load().is_err());
+
+        let backup = storage.back_up().unwrap();
+        assert_eq!(fs::read_to_string(backup).unwrap(), "{ not json");
+        assert!(storage.load().unwrap().is_empty());
+    }
+}
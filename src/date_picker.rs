@@ -0,0 +1,219 @@
+//! A calendar that drops down from a button to pick a date, for any GUI with date fields.
+//!
+//! The GUI owns a [`DatePicker`] per field, maps its [`Message`]s into its own, and writes the
+//! date [`DatePicker::update`] returns into the field. Calling [`DatePicker::show`] when a valid
+//! date is typed keeps the calendar on its month.
+
+use iced::{
+    Alignment, Element, Length,
+    widget::{Column, Row, Space, button, container, row, text},
+};
+use iced_aw::DropDown;
+use jiff::{ToSpan, civil::Date};
+
+const DAY_WIDTH: f32 = 36.0;
+const WEEKDAYS: [&str; 7] = ["Mo", "Tu", "We", "Th", "Fr", "Sa", "Su"];
+
+/// Whether the calendar is open, and which month it shows.
+#[derive(Debug, Default)]
+pub struct DatePicker {
+    open: bool,
+    /// The first day of the month shown.
+    month: Date,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub enum Message {
+    Toggle,
+    Dismiss,
+    PreviousMonth,
+    NextMonth,
+    Picked(Date),
+}
+
+impl DatePicker {
+    #[must_use]
+    pub fn is_open(&self) -> bool {
+        self.open
+    }
+
+    /// The first day of the month the calendar shows.
+    #[must_use]
+    pub fn month(&self) -> Date {
+        self.month
+    }
+
+    /// Shows the month of `date`.
+    pub fn show(&mut self, date: Date) {
+        self.month = date.first_of_month();
+    }
+
+    /// Opens on the month of the `selected` date, or else of the `earliest` one that can be picked,
+    /// and returns the date picked, if any.
+    pub fn update(
+        &mut self,
+        message: Message,
+        selected: Option<Date>,
+        earliest: Date,
+    ) -> Option<Date> {
+        match message {
+            Message::Toggle => {
+                self.open = !self.open;
+                if self.open {
+                    self.show(selected.unwrap_or(earliest));
+                }
+            }
+            Message::Dismiss => self.open = false,
+            Message::PreviousMonth => {
+                if let Ok(month) = self.month.checked_sub(1.month())
+                    && month >= earliest.first_of_month()
+                {
+                    self.month = month;
+                }
+            }
+            Message::NextMonth => {
+                if let Ok(month) = self.month.checked_add(1.month()) {
+                    self.month = month;
+                }
+            }
+            Message::Picked(date) => {
+                self.open = false;
+                return Some(date);
+            }
+        }
+
+        None
+    }
+
+    /// A button that opens the calendar, with the `selected` date highlighted and the days before
+    /// `earliest` disabled.
+    #[must_use]
+    pub fn view(&self, selected: Option<Date>, earliest: Date) -> Element<'_, Message> {
+        let toggle = button(text("Pick")).on_press(Message::Toggle);
+
+        DropDown::new(toggle, self.calendar(selected, earliest), self.open)
+            .alignment(iced_aw::drop_down::Alignment::BottomStart)
+            .on_dismiss(Message::Dismiss)
+            .into()
+    }
+
+    fn calendar(&self, selected: Option<Date>, earliest: Date) -> Element<'_, Message> {
+        // Months before the earliest one have nothing to pick.
+        let mut previous = button(text("<"));
+        if self.month > earliest.first_of_month() {
+            previous = previous.on_press(Message::PreviousMonth);
+        }
+        let header = row![
+            previous,
+            text(self.month.strftime("%B %Y").to_string())
+                .center()
+                .width(Length::Fill),
+            button(text(">")).on_press(Message::NextMonth),
+        ]
+        .align_y(Alignment::Center);
+
+        let mut calendar = Column::new().push(header).push(Row::with_children(
+            WEEKDAYS.map(|weekday| text(weekday).center().width(DAY_WIDTH).into()),
+        ));
+        for week in weeks(self.month) {
+            calendar = calendar.push(Row::with_children(week.map(|day| match day {
+                Some(day) => {
+                    let style = if Some(day) == selected {
+                        button::primary
+                    } else {
+                        button::text
+                    };
+                    let mut button = button(text(day.day()).center().width(Length::Fill))
+                        .style(style)
+                        .width(DAY_WIDTH);
+                    if day >= earliest {
+                        button = button.on_press(Message::Picked(day));
+                    }
+                    button.into()
+                }
+                None => Space::new().width(DAY_WIDTH).into(),
+            })));
+        }
+
+        container(calendar.spacing(2).width(7.0 * DAY_WIDTH))
+            .style(container::rounded_box)
+            .padding(5)
+            .into()
+    }
+}
+
+/// The days of the month starting at `first`, by week from Monday, with `None` for the days of
+/// other months.
+fn weeks(first: Date) -> Vec<[Option<Date>; 7]> {
+    let mut weeks = Vec::new();
+    let mut week = [None; 7];
+    let mut weekday = usize::from(first.weekday().to_monday_zero_offset().unsigned_abs());
+
+    for day in first
+        .series(1.day())
+        .take_while(|day| day.month() == first.month())
+    {
+        week[weekday] = Some(day);
+        weekday += 1;
+        if weekday == 7 {
+            weeks.push(week);
+            week = [None; 7];
+            weekday = 0;
+        }
+    }
+    if weekday > 0 {
+        weeks.push(week);
+    }
+
+    weeks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(year: i16, month: i8, day: i8) -> Date {
+        Date::new(year, month, day).unwrap()
+    }
+
+    #[test]
+    fn lays_out_weeks_from_monday() {
+        // March 2025 starts on a Saturday and ends on a Monday.
+        let weeks = weeks(date(2025, 3, 1));
+
+        assert_eq!(weeks.len(), 6);
+        assert_eq!(weeks[0][..5], [None; 5]);
+        assert_eq!(weeks[0][5], Some(date(2025, 3, 1)));
+        assert_eq!(weeks[5][0], Some(date(2025, 3, 31)));
+        assert_eq!(weeks[5][1..], [None; 6]);
+        assert_eq!(weeks.iter().flatten().flatten().count(), 31);
+    }
+
+    #[test]
+    fn opens_navigates_and_picks() {
+        let mut picker = DatePicker::default();
+        let earliest = date(2025, 1, 31);
+
+        picker.update(Message::Toggle, None, earliest);
+        assert!(picker.is_open());
+        assert_eq!(picker.month(), date(2025, 1, 1));
+
+        picker.update(Message::NextMonth, None, earliest);
+        assert_eq!(picker.month(), date(2025, 2, 1));
+        picker.update(Message::PreviousMonth, None, earliest);
+        picker.update(Message::PreviousMonth, None, earliest);
+        assert_eq!(picker.month(), date(2025, 1, 1));
+
+        assert_eq!(
+            picker.update(Message::Picked(date(2025, 2, 3)), None, earliest),
+            Some(date(2025, 2, 3))
+        );
+        assert!(!picker.is_open());
+
+        // It reopens on the selected date's month.
+        picker.update(Message::Toggle, Some(date(2026, 7, 4)), earliest);
+        assert_eq!(picker.month(), date(2026, 7, 1));
+        picker.update(Message::Dismiss, None, earliest);
+        assert!(!picker.is_open());
+    }
+}
@@ -0,0 +1,913 @@
+//! Flight Booker: book a one-way or return flight on valid dates.
+
+pub mod date;
+pub mod leg;
+
+use std::{fmt, sync::LazyLock};
+
+use iced::{
+    Alignment, Border, Element, Length, Subscription, Theme, keyboard,
+    widget::{Column, button, column, container, pick_list, row, scrollable, text, text_input},
+};
+use jiff::{
+    Zoned,
+    civil::Date,
+    tz::{self, TimeZone},
+};
+
+use crate::{
+    date_picker::{self, DatePicker},
+    keys,
+};
+
+use self::{
+    date::{DateFormat, Order, ParseError},
+    leg::Leg,
+};
+
+/// The flight booker's actions and their default shortcuts.
+pub const KEYS: &keys::Defaults = &[("book", "Enter")];
+
+const LABEL_WIDTH: f32 = 110.0;
+const TIME_WIDTH: f32 = 70.0;
+
+/// The fewest legs a multi-city trip has; with one it would be a one-way flight.
+const MIN_LEGS: usize = 2;
+
+/// Every time zone flights can leave from and land in, by IANA name.
+static ZONES: LazyLock<Vec<String>> = LazyLock::new(|| {
+    let mut zones: Vec<String> = tz::db().available().map(|name| name.to_string()).collect();
+    zones.sort();
+    zones
+});
+
+#[derive(Debug)]
+pub struct FlightBooker {
+    selected_flight: Flight,
+    one_way_flight: String,
+    /// The date typed in the one-way field, if it is valid, for its calendar.
+    one_way_flight_date: Option<Date>,
+    one_way_time: String,
+    return_flight: String,
+    return_flight_date: Option<Date>,
+    return_time: String,
+    /// The zone the flight leaves from and the return flight lands in, by IANA name.
+    departure_zone: String,
+    /// The zone the flight lands in and the return flight leaves from.
+    arrival_zone: String,
+    /// When each flight leaves, once its date and time are valid.
+    departure: Option<Zoned>,
+    return_departure: Option<Zoned>,
+    /// Why the date or time typed for each flight is invalid, shown under it.
+    one_way_error: Option<String>,
+    return_error: Option<String>,
+    one_way_picker: DatePicker,
+    return_picker: DatePicker,
+    /// The flights of a multi-city trip.
+    legs: Vec<Leg>,
+    /// How slashed dates are read.
+    order: Order,
+    /// How booked dates are shown, and valid dates rewritten when it changes.
+    format: DateFormat,
+    book: bool,
+    show_dialogue: bool,
+    dialogue_string: String,
+}
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    Book,
+    FlightSelected(Flight),
+    FormatSelected(DateFormat),
+    OneWayFlightChanged(String),
+    ReturnFlightChanged(String),
+    OneWayTimeChanged(String),
+    ReturnTimeChanged(String),
+    DepartureZoneSelected(String),
+    ArrivalZoneSelected(String),
+    AddLeg,
+    RemoveLeg(usize),
+    Leg(usize, leg::Message),
+    OneWayPicker(date_picker::Message),
+    ReturnPicker(date_picker::Message),
+}
+
+impl Default for FlightBooker {
+    /// Flights leave and land in UTC.
+    fn default() -> Self {
+        FlightBooker {
+            selected_flight: Flight::default(),
+            one_way_flight: String::new(),
+            one_way_flight_date: None,
+            one_way_time: String::new(),
+            return_flight: String::new(),
+            return_flight_date: None,
+            return_time: String::new(),
+            departure_zone: "UTC".to_string(),
+            arrival_zone: "UTC".to_string(),
+            departure: None,
+            return_departure: None,
+            one_way_error: None,
+            return_error: None,
+            one_way_picker: DatePicker::default(),
+            return_picker: DatePicker::default(),
+            legs: (0..MIN_LEGS).map(|_| Leg::default()).collect(),
+            order: Order::default(),
+            format: DateFormat::default(),
+            book: false,
+            show_dialogue: false,
+            dialogue_string: String::new(),
+        }
+    }
+}
+
+impl FlightBooker {
+    /// A flight booker that reads slashed dates in the order of the user's locale, with flights
+    /// leaving and landing in the system's time zone.
+    #[must_use]
+    pub fn from_env() -> Self {
+        let zone = TimeZone::system().iana_name().unwrap_or("UTC").to_string();
+        FlightBooker {
+            order: Order::from_env(),
+            departure_zone: zone.clone(),
+            arrival_zone: zone,
+            ..FlightBooker::default()
+        }
+    }
+
+    /// The confirmation shown after booking, if any.
+    #[must_use]
+    pub fn dialogue(&self) -> Option<&str> {
+        (self.book && self.show_dialogue).then_some(self.dialogue_string.as_str())
+    }
+
+    /// Why the date or time of the one-way flight is invalid, if it is.
+    #[must_use]
+    pub fn one_way_error(&self) -> Option<&str> {
+        self.one_way_error.as_deref()
+    }
+
+    /// Why the date or time of the return flight is invalid, if it is.
+    #[must_use]
+    pub fn return_error(&self) -> Option<&str> {
+        self.return_error.as_deref()
+    }
+
+    /// The legs of the multi-city trip.
+    #[must_use]
+    pub fn legs(&self) -> &[Leg] {
+        &self.legs
+    }
+
+    /// The first day the return flight can be picked on: the day the flight leaves, where the
+    /// return flight does, if that is after today there.
+    fn earliest_return(&self) -> Date {
+        let today = today(&self.arrival_zone);
+        let departure = match &self.departure {
+            Some(departure) => Some(
+                departure
+                    .with_time_zone(time_zone(&self.arrival_zone))
+                    .date(),
+            ),
+            None => self.one_way_flight_date,
+        };
+        departure.map_or(today, |date| date.max(today))
+    }
+
+    /// Reads each flight's date and time in the zone it leaves from, and each leg's date in the
+    /// departure zone, records each flight's error, and errors with the first of them. An empty
+    /// field can't be booked but shows no error, since it hasn't been typed in yet.
+    fn validate_flights(&mut self) -> anyhow::Result<()> {
+        if self.selected_flight == Flight::MultiCity {
+            (self.departure, self.return_departure) = (None, None);
+            (self.one_way_error, self.return_error) = (None, None);
+            return leg::validate(&mut self.legs, self.order, today(&self.departure_zone));
+        }
+
+        let one_way = date::parse(&self.one_way_flight, self.order);
+        self.one_way_flight_date = one_way.as_ref().ok().copied();
+        (self.departure, self.one_way_error) = leaves_at(
+            &self.one_way_flight,
+            one_way,
+            &self.one_way_time,
+            time_zone(&self.departure_zone),
+        );
+
+        (
+            self.return_flight_date,
+            self.return_departure,
+            self.return_error,
+        ) = match self.selected_flight {
+            Flight::OneWay | Flight::MultiCity => (None, None, None),
+            Flight::Return => {
+                let return_flight = date::parse(&self.return_flight, self.order);
+                let date = return_flight.as_ref().ok().copied();
+                let (departure, error) = leaves_at(
+                    &self.return_flight,
+                    return_flight,
+                    &self.return_time,
+                    time_zone(&self.arrival_zone),
+                );
+                (date, departure, error)
+            }
+        };
+
+        if let Some(departure) = &self.departure
+            && departure.timestamp() < Zoned::now().timestamp()
+        {
+            self.one_way_error = Some("the flight leaves in the past".to_string());
+        }
+
+        // The flights leave from different zones, so compare the instants they leave at.
+        if let (Some(departure), Some(return_departure)) = (&self.departure, &self.return_departure)
+            && return_departure.timestamp() < departure.timestamp()
+        {
+            self.return_error = Some("the return flight leaves before the flight".to_string());
+        }
+
+        match (&self.one_way_error, &self.return_error) {
+            (Some(error), _) | (None, Some(error)) => Err(anyhow::Error::msg(error.clone())),
+            (None, None) => {
+                let needed = match self.selected_flight {
+                    Flight::OneWay | Flight::MultiCity => self.departure.is_some(),
+                    Flight::Return => self.departure.is_some() && self.return_departure.is_some(),
+                };
+                if needed {
+                    Ok(())
+                } else {
+                    Err(anyhow::Error::msg("a flight date or time is missing"))
+                }
+            }
+        }
+    }
+
+    /// What was booked, once the flights are valid.
+    fn confirmation(&self) -> String {
+        let describe = |flight: Option<&Zoned>| {
+            flight
+                .map(|flight| self.describe(flight))
+                .unwrap_or_default()
+        };
+
+        match self.selected_flight {
+            Flight::OneWay => format!(
+                "You have booked a one-way flight on {}",
+                describe(self.departure.as_ref())
+            ),
+            Flight::Return => format!(
+                "You have booked a flight leaving on {} and returning on {}",
+                describe(self.departure.as_ref()),
+                describe(self.return_departure.as_ref()),
+            ),
+            Flight::MultiCity => format!(
+                "You have booked a multi-city trip:\n{}",
+                leg::itinerary(&self.legs, self.format)
+            ),
+        }
+    }
+
+    /// Shows dates in `format` from now on, and rewrites the valid dates already typed in it.
+    fn reformat(&mut self, format: DateFormat) {
+        self.format = format;
+        if let Some(date) = self.one_way_flight_date {
+            self.one_way_flight = format.format(date);
+        }
+        if let Some(date) = self.return_flight_date {
+            self.return_flight = format.format(date);
+        }
+        for leg in &mut self.legs {
+            leg.reformat(format);
+        }
+    }
+
+    /// The first day leg `index` can leave on, in the departure zone.
+    fn earliest_leg(&self, index: usize) -> Date {
+        leg::earliest(&self.legs, index, today(&self.departure_zone))
+    }
+
+    /// When `flight` leaves, in the chosen date format and the zone it leaves from.
+    fn describe(&self, flight: &Zoned) -> String {
+        format!(
+            "{} at {}",
+            self.format.format(flight.date()),
+            flight.strftime("%H:%M %Q")
+        )
+    }
+
+    pub fn update(&mut self, message: Message) {
+        match message {
+            Message::Book => {
+                self.show_dialogue = self.validate_flights().is_ok();
+                if self.show_dialogue {
+                    self.dialogue_string = self.confirmation();
+                }
+            }
+            Message::FlightSelected(flight) => {
+                self.show_dialogue = false;
+                self.selected_flight = flight;
+                self.book = self.validate_flights().is_ok();
+            }
+            Message::FormatSelected(format) => {
+                self.show_dialogue = false;
+                self.reformat(format);
+                self.book = self.validate_flights().is_ok();
+            }
+            Message::OneWayFlightChanged(date) => {
+                self.show_dialogue = false;
+                self.one_way_flight = date;
+                self.book = self.validate_flights().is_ok();
+                if let Some(date) = self.one_way_flight_date {
+                    self.one_way_picker.show(date);
+                }
+            }
+            Message::ReturnFlightChanged(date) => {
+                self.show_dialogue = false;
+                self.return_flight = date;
+                self.book = self.validate_flights().is_ok();
+                if let Some(date) = self.return_flight_date {
+                    self.return_picker.show(date);
+                }
+            }
+            Message::OneWayTimeChanged(time) => {
+                self.show_dialogue = false;
+                self.one_way_time = time;
+                self.book = self.validate_flights().is_ok();
+            }
+            Message::ReturnTimeChanged(time) => {
+                self.show_dialogue = false;
+                self.return_time = time;
+                self.book = self.validate_flights().is_ok();
+            }
+            Message::DepartureZoneSelected(zone) => {
+                self.show_dialogue = false;
+                self.departure_zone = zone;
+                self.book = self.validate_flights().is_ok();
+            }
+            Message::ArrivalZoneSelected(zone) => {
+                self.show_dialogue = false;
+                self.arrival_zone = zone;
+                self.book = self.validate_flights().is_ok();
+            }
+            Message::AddLeg => {
+                self.show_dialogue = false;
+                let origin = self.legs.last().map(Leg::destination).unwrap_or_default();
+                self.legs.push(Leg::starting_at(origin));
+                self.book = self.validate_flights().is_ok();
+            }
+            Message::RemoveLeg(index) => {
+                self.show_dialogue = false;
+                if self.legs.len() > MIN_LEGS && index < self.legs.len() {
+                    self.legs.remove(index);
+                }
+                self.book = self.validate_flights().is_ok();
+            }
+            Message::Leg(index, message) => {
+                self.show_dialogue = false;
+                let earliest = self.earliest_leg(index);
+                if let Some(leg) = self.legs.get_mut(index) {
+                    leg.update(message, self.order, self.format, earliest);
+                }
+                self.book = self.validate_flights().is_ok();
+            }
+            Message::OneWayPicker(message) => {
+                let earliest = today(&self.departure_zone);
+                if let Some(date) =
+                    self.one_way_picker
+                        .update(message, self.one_way_flight_date, earliest)
+                {
+                    self.update(Message::OneWayFlightChanged(self.format.format(date)));
+                }
+            }
+            Message::ReturnPicker(message) => {
+                let earliest = self.earliest_return();
+                if let Some(date) =
+                    self.return_picker
+                        .update(message, self.return_flight_date, earliest)
+                {
+                    self.update(Message::ReturnFlightChanged(self.format.format(date)));
+                }
+            }
+        }
+    }
+
+    /// Books when the book shortcut is pressed, even while a date is being typed.
+    pub fn subscription(&self) -> Subscription<Message> {
+        keyboard::listen().filter_map(|event| match keys::action("flight_booker", KEYS, &event)? {
+            "book" => Some(Message::Book),
+            _ => None,
+        })
+    }
+
+    #[must_use]
+    pub fn view(&self) -> Element<'_, Message> {
+        let flight = pick_list(
+            [Flight::OneWay, Flight::Return, Flight::MultiCity],
+            Some(self.selected_flight),
+            Message::FlightSelected,
+        )
+        .width(Length::Fill);
+
+        let format = labeled(
+            "Show dates as",
+            pick_list(
+                DateFormat::choices(self.order),
+                Some(self.format),
+                Message::FormatSelected,
+            )
+            .width(Length::Fill),
+        );
+
+        let flights = match self.selected_flight {
+            Flight::OneWay | Flight::Return => self.flights(),
+            Flight::MultiCity => self.legs_view(),
+        };
+
+        let mut book = button(text("Book").center().width(Length::Fill)).width(Length::Fill);
+        if self.book {
+            book = book.on_press(Message::Book);
+        }
+
+        let mut column = column![flight, format, flights, book]
+            .width(Length::Fill)
+            .align_x(Alignment::Center)
+            .padding(10)
+            .spacing(10);
+
+        if self.book && self.show_dialogue {
+            column = column.push(text(self.dialogue_string.clone()));
+        }
+
+        container(scrollable(column))
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .into()
+    }
+
+    /// The zone flights leave from, and the days of a multi-city trip's legs are in.
+    fn departure_zone(&self) -> Element<'_, Message> {
+        labeled(
+            "Leaving from",
+            pick_list(
+                ZONES.as_slice(),
+                Some(self.departure_zone.clone()),
+                Message::DepartureZoneSelected,
+            )
+            .width(Length::Fill),
+        )
+    }
+
+    /// The zones, dates and times of a one-way or return flight.
+    fn flights(&self) -> Element<'_, Message> {
+        let departure_zone = self.departure_zone();
+        let arrival_zone = labeled(
+            "Landing in",
+            pick_list(
+                ZONES.as_slice(),
+                Some(self.arrival_zone.clone()),
+                Message::ArrivalZoneSelected,
+            )
+            .width(Length::Fill),
+        );
+
+        let one_way_flight = date_field(
+            text_input("choose a flight date", &self.one_way_flight)
+                .id("one-way flight")
+                .on_input(Message::OneWayFlightChanged),
+            self.one_way_picker
+                .view(self.one_way_flight_date, today(&self.departure_zone))
+                .map(Message::OneWayPicker),
+            Some(
+                text_input("hh:mm", &self.one_way_time)
+                    .id("one-way time")
+                    .on_input(Message::OneWayTimeChanged),
+            ),
+            self.one_way_error.as_deref(),
+        );
+
+        let return_flight = if self.selected_flight == Flight::Return {
+            date_field(
+                text_input("choose a flight date", &self.return_flight)
+                    .id("return flight")
+                    .on_input(Message::ReturnFlightChanged),
+                self.return_picker
+                    .view(self.return_flight_date, self.earliest_return())
+                    .map(Message::ReturnPicker),
+                Some(
+                    text_input("hh:mm", &self.return_time)
+                        .id("return time")
+                        .on_input(Message::ReturnTimeChanged),
+                ),
+                self.return_error.as_deref(),
+            )
+        } else {
+            text_input("", &self.one_way_flight).into()
+        };
+
+        column![departure_zone, arrival_zone, one_way_flight, return_flight]
+            .spacing(10)
+            .into()
+    }
+
+    /// The departure zone and each leg of a multi-city trip, with buttons to remove a leg and add
+    /// another.
+    fn legs_view(&self) -> Element<'_, Message> {
+        let mut legs = Column::new().spacing(10).push(self.departure_zone());
+        for (index, leg) in self.legs.iter().enumerate() {
+            let mut remove = button(text("Remove"));
+            if self.legs.len() > MIN_LEGS {
+                remove = remove.on_press(Message::RemoveLeg(index));
+            }
+
+            legs = legs.push(
+                column![
+                    row![
+                        text(format!("Leg {}", index + 1)).width(Length::Fill),
+                        remove
+                    ]
+                    .align_y(Alignment::Center),
+                    leg.view(index + 1, self.earliest_leg(index))
+                        .map(move |message| Message::Leg(index, message)),
+                ]
+                .spacing(5),
+            );
+        }
+
+        legs.push(button(text("Add leg")).on_press(Message::AddLeg))
+            .into()
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Flight {
+    #[default]
+    OneWay,
+    Return,
+    MultiCity,
+}
+
+impl std::fmt::Display for Flight {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Flight::OneWay => "one-way flight",
+                Flight::Return => "return flight",
+                Flight::MultiCity => "multi-city trip",
+            }
+        )
+    }
+}
+
+/// When a flight leaves, from its `date` typed as `date_input` and its time typed in `zone`, or the
+/// error to show under it. Neither is known while a field is empty.
+fn leaves_at(
+    date_input: &str,
+    date: Result<Date, ParseError>,
+    time_input: &str,
+    zone: TimeZone,
+) -> (Option<Zoned>, Option<String>) {
+    let time = date::parse_time(time_input);
+    let error = field_error(date_input, date.as_ref().err())
+        .or_else(|| field_error(time_input, time.as_ref().err()));
+    if error.is_some() {
+        return (None, error);
+    }
+
+    let (Ok(date), Ok(time)) = (date, time) else {
+        return (None, None);
+    };
+    match date.to_datetime(time).to_zoned(zone) {
+        Ok(zoned) => (Some(zoned), None),
+        Err(error) => (None, Some(error.to_string())),
+    }
+}
+
+/// The error to show under a date or time field: none while it is empty.
+fn field_error(input: &str, error: Option<&impl fmt::Display>) -> Option<String> {
+    if input.is_empty() {
+        None
+    } else {
+        error.map(ToString::to_string)
+    }
+}
+
+/// The time zone named `name`, or UTC if there is none by that name.
+fn time_zone(name: &str) -> TimeZone {
+    TimeZone::get(name).unwrap_or(TimeZone::UTC)
+}
+
+/// Today's date in the time zone named `zone`.
+fn today(zone: &str) -> Date {
+    Zoned::now().with_time_zone(time_zone(zone)).date()
+}
+
+/// `element` with `label` before it.
+fn labeled<'a>(label: &'a str, element: impl Into<Element<'a, Message>>) -> Element<'a, Message> {
+    row![text(label).width(LABEL_WIDTH), element.into()]
+        .spacing(10)
+        .align_y(Alignment::Center)
+        .into()
+}
+
+/// A flight's date input with its calendar and time input, red with the error under them while
+/// the flight is invalid.
+fn date_field<'a, Message: Clone + 'a>(
+    input: text_input::TextInput<'a, Message>,
+    picker: Element<'a, Message>,
+    time: Option<text_input::TextInput<'a, Message>>,
+    error: Option<&'a str>,
+) -> Element<'a, Message> {
+    let time = time.map(|time| time.width(TIME_WIDTH));
+    match error {
+        Some(error) => Column::new()
+            .push(
+                row![input.style(invalid), picker]
+                    .push(time.map(|time| time.style(invalid)))
+                    .spacing(5),
+            )
+            .push(text(error).style(text::danger).size(14))
+            .spacing(2)
+            .into(),
+        None => row![input, picker].push(time).spacing(5).into(),
+    }
+}
+
+/// The default text input style on a red background.
+fn invalid(theme: &Theme, status: text_input::Status) -> text_input::Style {
+    let palette = theme.extended_palette();
+    let style = text_input::default(theme, status);
+
+    text_input::Style {
+        background: palette.danger.weak.color.into(),
+        border: Border {
+            color: palette.danger.strong.color,
+            ..style.border
+        },
+        value: palette.danger.weak.text,
+        placeholder: palette.danger.weak.text,
+        ..style
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A return booker with both flights typed, the first leaving from `departure_zone` and the
+    /// return flight from `arrival_zone`.
+    fn booker(
+        (date, time, departure_zone): (&str, &str, &str),
+        (return_date, return_time, arrival_zone): (&str, &str, &str),
+    ) -> FlightBooker {
+        let mut booker = FlightBooker::default();
+        booker.update(Message::FlightSelected(Flight::Return));
+        booker.update(Message::DepartureZoneSelected(departure_zone.to_string()));
+        booker.update(Message::ArrivalZoneSelected(arrival_zone.to_string()));
+        booker.update(Message::OneWayFlightChanged(date.to_string()));
+        booker.update(Message::OneWayTimeChanged(time.to_string()));
+        booker.update(Message::ReturnFlightChanged(return_date.to_string()));
+        booker.update(Message::ReturnTimeChanged(return_time.to_string()));
+        booker
+    }
+
+    #[test]
+    fn books_valid_return_flight() {
+        let mut booker = booker(
+            ("1.2.2099", "9:30", "Europe/Paris"),
+            ("3.2.2099", "18:00", "America/New_York"),
+        );
+        booker.update(Message::Book);
+
+        assert_eq!(
+            booker.dialogue(),
+            Some(
+                "You have booked a flight leaving on 1.2.2099 at 09:30 Europe/Paris and \
+                 returning on 3.2.2099 at 18:00 America/New_York"
+            )
+        );
+    }
+
+    #[test]
+    fn rejects_return_before_departure() {
+        let mut booker = booker(("3.2.2099", "12:00", "UTC"), ("1.2.2099", "12:00", "UTC"));
+
+        assert!(booker.validate_flights().is_err());
+        assert_eq!(booker.one_way_error(), None);
+        assert_eq!(
+            booker.return_error(),
+            Some("the return flight leaves before the flight")
+        );
+    }
+
+    #[test]
+    fn compares_instants_across_zones() {
+        // 09:00 in Tokyo is midnight UTC, and 20:00 the day before in Los Angeles is 04:00 UTC.
+        let later = booker(
+            ("1.2.2099", "09:00", "Asia/Tokyo"),
+            ("31.1.2099", "20:00", "America/Los_Angeles"),
+        );
+        assert!(later.book);
+
+        // 15:00 in Los Angeles is 23:00 UTC, before the flight leaves.
+        let earlier = booker(
+            ("1.2.2099", "09:00", "Asia/Tokyo"),
+            ("31.1.2099", "15:00", "America/Los_Angeles"),
+        );
+        assert_eq!(
+            earlier.return_error(),
+            Some("the return flight leaves before the flight")
+        );
+    }
+
+    #[test]
+    fn rejects_departures_in_the_past() {
+        let mut booker = FlightBooker::default();
+        booker.update(Message::OneWayFlightChanged("1.2.2025".to_string()));
+        booker.update(Message::OneWayTimeChanged("12:00".to_string()));
+
+        assert_eq!(
+            booker.one_way_error(),
+            Some("the flight leaves in the past")
+        );
+        assert!(!booker.book);
+    }
+
+    #[test]
+    fn errors_belong_to_their_field() {
+        let mut booker = FlightBooker::default();
+        booker.update(Message::FlightSelected(Flight::Return));
+        assert_eq!(
+            (booker.one_way_error(), booker.return_error()),
+            (None, None)
+        );
+        assert!(!booker.book);
+
+        booker.update(Message::OneWayFlightChanged("1.2".to_string()));
+        assert_eq!(
+            booker.one_way_error(),
+            Some("expected '.' and the year at character 4")
+        );
+        assert_eq!(booker.return_error(), None);
+
+        booker.update(Message::OneWayFlightChanged("1.2.2099".to_string()));
+        booker.update(Message::OneWayTimeChanged("9:5".to_string()));
+        assert_eq!(
+            booker.one_way_error(),
+            Some("the minute must have two digits at character 3")
+        );
+
+        booker.update(Message::OneWayTimeChanged("9:05".to_string()));
+        booker.update(Message::ReturnFlightChanged("30.2.2099".to_string()));
+        assert_eq!(booker.one_way_error(), None);
+        assert!(booker.return_error().is_some());
+        assert!(!booker.book);
+
+        // A one-way flight ignores the return field.
+        booker.update(Message::FlightSelected(Flight::OneWay));
+        assert_eq!(booker.return_error(), None);
+        assert!(booker.book);
+    }
+
+    #[test]
+    fn pickers_and_fields_stay_in_sync() {
+        let mut booker = FlightBooker::default();
+        booker.update(Message::FlightSelected(Flight::Return));
+        booker.update(Message::OneWayFlightChanged("2099-03-14".to_string()));
+        booker.update(Message::OneWayTimeChanged("12:00".to_string()));
+        assert_eq!(
+            booker.one_way_picker.month(),
+            Date::new(2099, 3, 1).unwrap()
+        );
+
+        // The return calendar opens on the departure, the earliest day it can pick.
+        booker.update(Message::ReturnPicker(date_picker::Message::Toggle));
+        assert_eq!(booker.return_picker.month(), Date::new(2099, 3, 1).unwrap());
+
+        let picked = Date::new(2099, 3, 20).unwrap();
+        booker.update(Message::ReturnPicker(date_picker::Message::Picked(picked)));
+        booker.update(Message::ReturnTimeChanged("12:00".to_string()));
+        assert_eq!(booker.return_flight, "20.3.2099");
+        assert!(!booker.return_picker.is_open());
+        assert!(booker.book);
+    }
+
+    #[test]
+    fn books_multi_city_trips() {
+        let mut booker = FlightBooker::default();
+        booker.update(Message::FlightSelected(Flight::MultiCity));
+        for (index, (origin, destination, date)) in [
+            ("Paris", "Rome", "1.2.2099"),
+            ("Rome", "Berlin", "3.2.2099"),
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            for message in [
+                leg::Message::OriginChanged(origin.to_string()),
+                leg::Message::DestinationChanged(destination.to_string()),
+                leg::Message::DateChanged(date.to_string()),
+            ] {
+                booker.update(Message::Leg(index, message));
+            }
+        }
+        assert!(booker.book);
+
+        // A new leg leaves from where the last one lands.
+        booker.update(Message::AddLeg);
+        assert!(!booker.book);
+        for message in [
+            leg::Message::DestinationChanged("Oslo".to_string()),
+            leg::Message::DateChanged("2.2.2099".to_string()),
+        ] {
+            booker.update(Message::Leg(2, message));
+        }
+        assert_eq!(
+            booker.legs()[2].error(),
+            Some("the flight leaves before leg 2")
+        );
+
+        booker.update(Message::RemoveLeg(1));
+        booker.update(Message::Book);
+        assert_eq!(
+            booker.dialogue(),
+            Some(
+                "You have booked a multi-city trip:\n1. Paris to Rome on 1.2.2099\n\
+                 2. Berlin to Oslo on 2.2.2099"
+            )
+        );
+
+        // A trip keeps at least two legs.
+        booker.update(Message::RemoveLeg(0));
+        assert_eq!(booker.legs().len(), MIN_LEGS);
+    }
+
+    #[test]
+    fn legs_leave_after_today_in_the_departure_zone() {
+        // Kiritimati is 25 hours ahead of Pago Pago, so its today is always later.
+        let mut booker = FlightBooker::default();
+        booker.update(Message::FlightSelected(Flight::MultiCity));
+        booker.update(Message::DepartureZoneSelected(
+            "Pacific/Kiritimati".to_string(),
+        ));
+        let pago_pago = DateFormat::Dotted.format(today("Pacific/Pago_Pago"));
+        booker.update(Message::Leg(0, leg::Message::DateChanged(pago_pago)));
+
+        assert_eq!(
+            booker.legs()[0].error(),
+            Some("the flight leaves in the past")
+        );
+        assert!(booker.earliest_leg(1) > today("Pacific/Pago_Pago"));
+    }
+
+    #[test]
+    fn validates_dates_and_times() {
+        let read = |date, time| {
+            leaves_at(
+                date,
+                date::parse(date, Order::default()),
+                time,
+                TimeZone::UTC,
+            )
+        };
+
+        assert!(read("29.2.2024", "0:00").0.is_some());
+        assert!(read("29.2.2025", "0:00").1.is_some());
+        assert!(read("1.2", "0:00").1.is_some());
+        assert!(read("1.2.2025.9", "0:00").1.is_some());
+        assert!(read("1.2.2025", "25:00").1.is_some());
+        // Empty fields aren't errors yet.
+        assert_eq!(read("", ""), (None, None));
+        assert_eq!(read("1.2.2025", ""), (None, None));
+    }
+
+    #[test]
+    fn shows_dates_in_the_chosen_format() {
+        let mut booker = FlightBooker {
+            order: Order::DayMonthYear,
+            ..FlightBooker::default()
+        };
+        booker.update(Message::FlightSelected(Flight::Return));
+        booker.update(Message::OneWayFlightChanged("2099-02-01".to_string()));
+        booker.update(Message::OneWayTimeChanged("08:15".to_string()));
+        booker.update(Message::ReturnFlightChanged("3/2/2099".to_string()));
+        booker.update(Message::ReturnTimeChanged("20:45".to_string()));
+        booker.update(Message::FormatSelected(DateFormat::Iso));
+        assert_eq!(booker.return_flight, "2099-02-03");
+
+        booker.update(Message::Book);
+        assert_eq!(
+            booker.dialogue(),
+            Some(
+                "You have booked a flight leaving on 2099-02-01 at 08:15 UTC and returning on \
+                 2099-02-03 at 20:45 UTC"
+            )
+        );
+
+        // Invalid dates are left as typed.
+        booker.update(Message::OneWayFlightChanged("1.2.".to_string()));
+        booker.update(Message::FormatSelected(DateFormat::Dotted));
+        assert_eq!(booker.one_way_flight, "1.2.");
+        assert_eq!(booker.return_flight, "3.2.2099");
+    }
+}
@@ -0,0 +1,488 @@
+//! Reading dates typed in several formats, and times of day, with errors that point at the bad
+//! character.
+//!
+//! The fields are scanned here rather than with [`jiff::fmt::strtime`], since jiff's parse errors
+//! only describe what went wrong, not where. jiff checks the values against the calendar.
+
+use std::{env, fmt};
+
+use jiff::civil::{Date, Time};
+
+/// The order of the day, month and year in a date written with slashes.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Order {
+    /// 3/14/2025, as in the US.
+    #[default]
+    MonthDayYear,
+    /// 14/3/2025, as in most of the world.
+    DayMonthYear,
+    /// 2025/3/14, as in East Asia.
+    YearMonthDay,
+}
+
+impl Order {
+    /// The order `locale`, such as "en_US.UTF-8" or "de-DE", writes dates in. A locale without a
+    /// region, such as "C", gets the default.
+    #[must_use]
+    pub fn for_locale(locale: &str) -> Order {
+        let name = locale.split(['.', '@']).next().unwrap_or_default();
+        let mut parts = name.split(['_', '-']);
+        let language = parts.next().unwrap_or_default().to_ascii_lowercase();
+        let region = parts.next().unwrap_or_default().to_ascii_uppercase();
+
+        if matches!(language.as_str(), "ja" | "ko" | "zh" | "hu" | "lt" | "mn") {
+            Order::YearMonthDay
+        } else if region.is_empty() || matches!(region.as_str(), "US" | "PH" | "FM" | "MH" | "PW") {
+            Order::MonthDayYear
+        } else {
+            Order::DayMonthYear
+        }
+    }
+
+    /// The order of the locale in `LC_ALL`, `LC_TIME` or `LANG`, whichever is set first.
+    #[must_use]
+    pub fn from_env() -> Order {
+        ["LC_ALL", "LC_TIME", "LANG"]
+            .into_iter()
+            .filter_map(|variable| env::var(variable).ok())
+            .find(|locale| !locale.is_empty())
+            .map_or_else(Order::default, |locale| Order::for_locale(&locale))
+    }
+
+    fn fields(self) -> [DateField; 3] {
+        match self {
+            Order::MonthDayYear => [DateField::Month, DateField::Day, DateField::Year],
+            Order::DayMonthYear => [DateField::Day, DateField::Month, DateField::Year],
+            Order::YearMonthDay => [DateField::Year, DateField::Month, DateField::Day],
+        }
+    }
+}
+
+/// How dates are shown. Dates in any format can be typed whichever is preferred.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum DateFormat {
+    /// 14.3.2025, as in the 7GUIs task.
+    #[default]
+    Dotted,
+    /// 2025-03-14, ISO 8601.
+    Iso,
+    /// With slashes in the given order.
+    Slashed(Order),
+}
+
+impl DateFormat {
+    /// The formats to choose from where slashed dates are read in `order`.
+    #[must_use]
+    pub fn choices(order: Order) -> [DateFormat; 3] {
+        [
+            DateFormat::Dotted,
+            DateFormat::Iso,
+            DateFormat::Slashed(order),
+        ]
+    }
+
+    /// Writes `date` in this format.
+    #[must_use]
+    pub fn format(self, date: Date) -> String {
+        let pattern = match self {
+            DateFormat::Dotted => "%-d.%-m.%Y",
+            DateFormat::Iso => "%Y-%m-%d",
+            DateFormat::Slashed(Order::MonthDayYear) => "%-m/%-d/%Y",
+            DateFormat::Slashed(Order::DayMonthYear) => "%-d/%-m/%Y",
+            DateFormat::Slashed(Order::YearMonthDay) => "%Y/%-m/%-d",
+        };
+        date.strftime(pattern).to_string()
+    }
+}
+
+impl fmt::Display for DateFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            DateFormat::Dotted => "dd.mm.yyyy",
+            DateFormat::Iso => "yyyy-mm-dd",
+            DateFormat::Slashed(Order::MonthDayYear) => "mm/dd/yyyy",
+            DateFormat::Slashed(Order::DayMonthYear) => "dd/mm/yyyy",
+            DateFormat::Slashed(Order::YearMonthDay) => "yyyy/mm/dd",
+        })
+    }
+}
+
+/// Why a date or time couldn't be read, and where.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseError {
+    /// The character the error is at, counting from 1.
+    pub position: usize,
+    pub message: String,
+}
+
+impl ParseError {
+    fn new(position: usize, message: impl Into<String>) -> Self {
+        ParseError {
+            position,
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at character {}", self.message, self.position)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// A number in a date or a time, named in errors.
+trait Field: fmt::Display + Copy {
+    /// What is wrong with the field having `digits` digits, if anything.
+    fn digits_error(self, digits: usize) -> Option<String>;
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum DateField {
+    Day,
+    Month,
+    Year,
+}
+
+impl Field for DateField {
+    fn digits_error(self, digits: usize) -> Option<String> {
+        match self {
+            DateField::Day | DateField::Month if digits > 2 => {
+                Some(format!("the {self} has more than two digits"))
+            }
+            DateField::Year if digits != 4 => Some("the year must have four digits".to_string()),
+            DateField::Day | DateField::Month | DateField::Year => None,
+        }
+    }
+}
+
+impl fmt::Display for DateField {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            DateField::Day => "day",
+            DateField::Month => "month",
+            DateField::Year => "year",
+        })
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum TimeField {
+    Hour,
+    Minute,
+}
+
+impl Field for TimeField {
+    fn digits_error(self, digits: usize) -> Option<String> {
+        match self {
+            TimeField::Hour if digits > 2 => Some("the hour has more than two digits".to_string()),
+            TimeField::Minute if digits != 2 => Some("the minute must have two digits".to_string()),
+            TimeField::Hour | TimeField::Minute => None,
+        }
+    }
+}
+
+impl fmt::Display for TimeField {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            TimeField::Hour => "hour",
+            TimeField::Minute => "minute",
+        })
+    }
+}
+
+/// Reads a date as `yyyy-mm-dd` (ISO 8601), `dd.mm.yyyy`, or with slashes in `order`. Surrounding
+/// whitespace is ignored, days and months may have one digit, and years must have four.
+///
+/// # Errors
+///
+/// Errors at the first character that doesn't belong to such a date, or at the field that is not
+/// on the calendar.
+pub fn parse(input: &str, order: Order) -> Result<Date, ParseError> {
+    let chars: Vec<char> = input.trim_end().chars().collect();
+    let mut index = chars.iter().take_while(|c| c.is_whitespace()).count();
+
+    let separator = separator(&chars, index)?;
+    let fields = match separator {
+        '-' => Order::YearMonthDay.fields(),
+        '.' => Order::DayMonthYear.fields(),
+        _ => order.fields(),
+    };
+
+    let (mut day, mut month, mut year) = ((0, 0), (0, 0), (0, 0));
+    for (n, field) in fields.into_iter().enumerate() {
+        if n > 0 {
+            index = after_separator(&chars, index, separator, field)?;
+        }
+
+        let start = index;
+        let value;
+        (value, index) = number(&chars, start, field)?;
+        match field {
+            DateField::Day => day = (value, start),
+            DateField::Month => month = (value, start),
+            DateField::Year => year = (value, start),
+        }
+    }
+
+    at_end(&chars, index, fields[2])?;
+    calendar_date(year, month, day)
+}
+
+/// Reads a time of day as `hh:mm` on a 24-hour clock. Surrounding whitespace is ignored, and hours
+/// may have one digit.
+///
+/// # Errors
+///
+/// Errors at the first character that doesn't belong to such a time, or at the field that is out of
+/// range.
+pub fn parse_time(input: &str) -> Result<Time, ParseError> {
+    let chars: Vec<char> = input.trim_end().chars().collect();
+    let start = chars.iter().take_while(|c| c.is_whitespace()).count();
+    if start == chars.len() {
+        return Err(ParseError::new(start + 1, "expected a time"));
+    }
+
+    let (hour, index) = number(&chars, start, TimeField::Hour)?;
+    let minute_start = after_separator(&chars, index, ':', TimeField::Minute)?;
+    let (minute, index) = number(&chars, minute_start, TimeField::Minute)?;
+    at_end(&chars, index, TimeField::Minute)?;
+
+    // Both have at most two digits, so they fit.
+    let hour = i8::try_from(hour).unwrap_or(i8::MAX);
+    let minute = i8::try_from(minute).unwrap_or(i8::MAX);
+    if hour > 23 {
+        return Err(ParseError::new(
+            start + 1,
+            format!("hour {hour} is not between 0 and 23"),
+        ));
+    }
+    if minute > 59 {
+        return Err(ParseError::new(
+            minute_start + 1,
+            format!("minute {minute} is not between 0 and 59"),
+        ));
+    }
+
+    Time::new(hour, minute, 0, 0).map_err(|error| ParseError::new(start + 1, error.to_string()))
+}
+
+/// The first character after the leading digits, which must be a separator.
+fn separator(chars: &[char], start: usize) -> Result<char, ParseError> {
+    let Some(index) = (start..chars.len()).find(|&index| !chars[index].is_ascii_digit()) else {
+        return Err(ParseError::new(
+            chars.len() + 1,
+            if chars.len() == start {
+                "expected a date"
+            } else {
+                "expected '-', '.' or '/'"
+            },
+        ));
+    };
+
+    match chars[index] {
+        separator @ ('-' | '.' | '/') => Ok(separator),
+        c if index == start => Err(ParseError::new(
+            index + 1,
+            format!("expected a digit, found {c:?}"),
+        )),
+        c => Err(ParseError::new(
+            index + 1,
+            format!("expected '-', '.' or '/', found {c:?}"),
+        )),
+    }
+}
+
+/// The index after the `separator` at `index`, which comes before `next`.
+fn after_separator(
+    chars: &[char],
+    index: usize,
+    separator: char,
+    next: impl Field,
+) -> Result<usize, ParseError> {
+    match chars.get(index) {
+        Some(c) if *c == separator => Ok(index + 1),
+        Some(c) => Err(ParseError::new(
+            index + 1,
+            format!("expected {separator:?}, found {c:?}"),
+        )),
+        None => Err(ParseError::new(
+            index + 1,
+            format!("expected {separator:?} and the {next}"),
+        )),
+    }
+}
+
+/// Errors if anything follows the `last` field, which ends at `index`.
+fn at_end(chars: &[char], index: usize, last: impl Field) -> Result<(), ParseError> {
+    match chars.get(index) {
+        Some(c) => Err(ParseError::new(
+            index + 1,
+            format!("unexpected {c:?} after the {last}"),
+        )),
+        None => Ok(()),
+    }
+}
+
+/// Reads `field`'s digits from `start`, returning its value and the index after it.
+fn number(chars: &[char], start: usize, field: impl Field) -> Result<(i16, usize), ParseError> {
+    let mut end = start;
+    while chars.get(end).is_some_and(char::is_ascii_digit) {
+        end += 1;
+    }
+
+    let digits = end - start;
+    let error = if digits == 0 {
+        match chars.get(end) {
+            Some(c) => Some(format!("expected the {field}, found {c:?}")),
+            None => Some(format!("expected the {field}")),
+        }
+    } else {
+        field.digits_error(digits)
+    };
+    if let Some(message) = error {
+        return Err(ParseError::new(start + 1, message));
+    }
+
+    let value = chars[start..end].iter().fold(0, |value, digit| {
+        value * 10 + i16::from(*digit as u8 - b'0')
+    });
+    Ok((value, end))
+}
+
+/// The date with these values, each with the index it was read at, if it is on the calendar.
+fn calendar_date(
+    year: (i16, usize),
+    month: (i16, usize),
+    day: (i16, usize),
+) -> Result<Date, ParseError> {
+    // Days and months have at most two digits, so they fit.
+    let month_number = i8::try_from(month.0).unwrap_or(i8::MAX);
+    let day_number = i8::try_from(day.0).unwrap_or(i8::MAX);
+    if !(1..=12).contains(&month_number) {
+        return Err(ParseError::new(
+            month.1 + 1,
+            format!("month {month_number} is not between 1 and 12"),
+        ));
+    }
+
+    let first = Date::new(year.0, month_number, 1)
+        .map_err(|error| ParseError::new(year.1 + 1, error.to_string()))?;
+    if !(1..=first.days_in_month()).contains(&day_number) {
+        return Err(ParseError::new(
+            day.1 + 1,
+            format!("{} has no day {day_number}", first.strftime("%B %Y")),
+        ));
+    }
+
+    Date::new(year.0, month_number, day_number)
+        .map_err(|error| ParseError::new(day.1 + 1, error.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn error(input: &str) -> String {
+        parse(input, Order::MonthDayYear).unwrap_err().to_string()
+    }
+
+    #[test]
+    fn reads_every_format() {
+        let date = Date::new(2025, 3, 14).unwrap();
+
+        for input in ["2025-03-14", "14.3.2025", "03/14/2025", " 14.03.2025 "] {
+            assert_eq!(parse(input, Order::MonthDayYear), Ok(date), "{input}");
+        }
+        assert_eq!(parse("14/3/2025", Order::DayMonthYear), Ok(date));
+        assert_eq!(parse("2025/3/14", Order::YearMonthDay), Ok(date));
+        assert_eq!(parse("29.2.2024", Order::default()).unwrap().day(), 29);
+    }
+
+    #[test]
+    fn points_at_the_error() {
+        assert_eq!(error(""), "expected a date at character 1");
+        assert_eq!(error("1.2"), "expected '.' and the year at character 4");
+        assert_eq!(
+            error("1.2.2025.9"),
+            "unexpected '.' after the year at character 9"
+        );
+        assert_eq!(error("1.2/2025"), "expected '.', found '/' at character 4");
+        assert_eq!(
+            error("1..2025"),
+            "expected the month, found '.' at character 3"
+        );
+        assert_eq!(
+            error("a.2.2025"),
+            "expected a digit, found 'a' at character 1"
+        );
+        assert_eq!(
+            error("1 2 2025"),
+            "expected '-', '.' or '/', found ' ' at character 2"
+        );
+        assert_eq!(error("20250314"), "expected '-', '.' or '/' at character 9");
+        assert_eq!(
+            error("1.2.25"),
+            "the year must have four digits at character 5"
+        );
+        assert_eq!(
+            error("  2025-13-01"),
+            "month 13 is not between 1 and 12 at character 8"
+        );
+        assert_eq!(
+            error("02/29/2025"),
+            "February 2025 has no day 29 at character 4"
+        );
+        assert_eq!(
+            error("001.2.2025"),
+            "the day has more than two digits at character 1"
+        );
+    }
+
+    #[test]
+    fn reads_times() {
+        assert_eq!(parse_time(" 9:05 "), Ok(Time::constant(9, 5, 0, 0)));
+        assert_eq!(parse_time("23:59"), Ok(Time::constant(23, 59, 0, 0)));
+
+        let error = |input| parse_time(input).unwrap_err().to_string();
+        assert_eq!(error(""), "expected a time at character 1");
+        assert_eq!(error("9"), "expected ':' and the minute at character 2");
+        assert_eq!(
+            error("9:5"),
+            "the minute must have two digits at character 3"
+        );
+        assert_eq!(
+            error("24:00"),
+            "hour 24 is not between 0 and 23 at character 1"
+        );
+        assert_eq!(
+            error("9:60"),
+            "minute 60 is not between 0 and 59 at character 3"
+        );
+        assert_eq!(
+            error("9:30pm"),
+            "unexpected 'p' after the minute at character 5"
+        );
+    }
+
+    #[test]
+    fn orders_follow_the_locale() {
+        assert_eq!(Order::for_locale("en_US.UTF-8"), Order::MonthDayYear);
+        assert_eq!(Order::for_locale("C"), Order::MonthDayYear);
+        assert_eq!(Order::for_locale("de-DE"), Order::DayMonthYear);
+        assert_eq!(Order::for_locale("en_GB@euro"), Order::DayMonthYear);
+        assert_eq!(Order::for_locale("ja_JP.UTF-8"), Order::YearMonthDay);
+    }
+
+    #[test]
+    fn formats_dates() {
+        let date = Date::new(2025, 3, 4).unwrap();
+        let formatted = DateFormat::choices(Order::DayMonthYear).map(|format| format.format(date));
+
+        assert_eq!(formatted, ["4.3.2025", "2025-03-04", "4/3/2025"]);
+        for format in DateFormat::choices(Order::default()) {
+            assert_eq!(parse(&format.format(date), Order::default()), Ok(date));
+        }
+    }
+}
@@ -0,0 +1,260 @@
+//! One flight of a multi-city trip, from an origin to a destination on a date.
+
+use iced::{
+    Element,
+    widget::{column, row, text_input},
+};
+use jiff::civil::Date;
+
+use super::{
+    date::{self, DateFormat, Order},
+    date_field, field_error,
+};
+use crate::date_picker::{self, DatePicker};
+
+/// A leg as typed, with what is wrong with it.
+#[derive(Debug, Default)]
+pub struct Leg {
+    origin: String,
+    destination: String,
+    date: String,
+    /// The date, if it is valid, for its calendar.
+    parsed: Option<Date>,
+    /// Why the leg can't be booked, shown under it.
+    error: Option<String>,
+    picker: DatePicker,
+}
+
+#[derive(Clone, Debug)]
+pub enum Message {
+    OriginChanged(String),
+    DestinationChanged(String),
+    DateChanged(String),
+    Picker(date_picker::Message),
+}
+
+impl Leg {
+    /// A leg leaving from `origin`, such as where the last one lands.
+    #[must_use]
+    pub fn starting_at(origin: &str) -> Self {
+        Leg {
+            origin: origin.to_string(),
+            ..Leg::default()
+        }
+    }
+
+    #[must_use]
+    pub fn destination(&self) -> &str {
+        &self.destination
+    }
+
+    /// Why the leg can't be booked, if it can't.
+    #[must_use]
+    pub fn error(&self) -> Option<&str> {
+        self.error.as_deref()
+    }
+
+    /// Handles `message`, writing picked dates in `format` and reading typed ones in `order`. The
+    /// calendar can't pick days before `earliest`.
+    pub fn update(&mut self, message: Message, order: Order, format: DateFormat, earliest: Date) {
+        match message {
+            Message::OriginChanged(origin) => self.origin = origin,
+            Message::DestinationChanged(destination) => self.destination = destination,
+            Message::DateChanged(date) => {
+                if let Ok(date) = date::parse(&date, order) {
+                    self.picker.show(date);
+                }
+                self.date = date;
+            }
+            Message::Picker(message) => {
+                if let Some(date) = self.picker.update(message, self.parsed, earliest) {
+                    self.date = format.format(date);
+                }
+            }
+        }
+    }
+
+    /// Rewrites the date in `format`, if it is valid.
+    pub fn reformat(&mut self, format: DateFormat) {
+        if let Some(date) = self.parsed {
+            self.date = format.format(date);
+        }
+    }
+
+    /// The leg's fields, numbered `number` for their ids, with the calendar starting at
+    /// `earliest`.
+    #[must_use]
+    pub fn view(&self, number: usize, earliest: Date) -> Element<'_, Message> {
+        let places = row![
+            text_input("from", &self.origin)
+                .id(format!("leg {number} origin"))
+                .on_input(Message::OriginChanged),
+            text_input("to", &self.destination)
+                .id(format!("leg {number} destination"))
+                .on_input(Message::DestinationChanged),
+        ]
+        .spacing(5);
+
+        let date = date_field(
+            text_input("choose a flight date", &self.date)
+                .id(format!("leg {number} date"))
+                .on_input(Message::DateChanged),
+            self.picker.view(self.parsed, earliest).map(Message::Picker),
+            None,
+            self.error.as_deref(),
+        );
+
+        column![places, date].spacing(5).into()
+    }
+
+    fn is_complete(&self) -> bool {
+        self.parsed.is_some()
+            && !self.origin.trim().is_empty()
+            && !self.destination.trim().is_empty()
+    }
+}
+
+/// The first day leg `index` can leave on: the latest date before it, if that is after `today`.
+#[must_use]
+pub fn earliest(legs: &[Leg], index: usize, today: Date) -> Date {
+    legs[..index.min(legs.len())]
+        .iter()
+        .filter_map(|leg| leg.parsed)
+        .fold(today, Date::max)
+}
+
+/// Reads every leg's date in `order`, records each leg's error, and errors with the first of them,
+/// naming its leg. A leg can't leave before `today` or before an earlier leg.
+///
+/// # Errors
+///
+/// Errors if a leg is invalid or incomplete.
+pub fn validate(legs: &mut [Leg], order: Order, today: Date) -> anyhow::Result<()> {
+    let mut latest: Option<(usize, Date)> = None;
+    for (index, leg) in legs.iter_mut().enumerate() {
+        let date = date::parse(&leg.date, order);
+        leg.parsed = date.as_ref().ok().copied();
+        leg.error = field_error(&leg.date, date.as_ref().err());
+
+        if let Some(date) = leg.parsed {
+            match latest {
+                _ if date < today => leg.error = Some("the flight leaves in the past".to_string()),
+                Some((before, latest)) if date < latest => {
+                    leg.error = Some(format!("the flight leaves before leg {}", before + 1));
+                }
+                Some(_) | None => latest = Some((index, date)),
+            }
+        }
+
+        let origin = leg.origin.trim();
+        if leg.error.is_none()
+            && !origin.is_empty()
+            && origin.eq_ignore_ascii_case(leg.destination.trim())
+        {
+            leg.error = Some("the flight lands where it leaves from".to_string());
+        }
+    }
+
+    if let Some((index, error)) = legs
+        .iter()
+        .enumerate()
+        .find_map(|(index, leg)| Some((index, leg.error.as_ref()?)))
+    {
+        return Err(anyhow::Error::msg(format!("leg {}: {error}", index + 1)));
+    }
+
+    if legs.iter().all(Leg::is_complete) {
+        Ok(())
+    } else {
+        Err(anyhow::Error::msg(
+            "a leg's origin, destination or date is missing",
+        ))
+    }
+}
+
+/// Every leg on its own numbered line, with its date in `format`.
+#[must_use]
+pub fn itinerary(legs: &[Leg], format: DateFormat) -> String {
+    legs.iter()
+        .enumerate()
+        .map(|(index, leg)| {
+            format!(
+                "{}. {} to {} on {}",
+                index + 1,
+                leg.origin.trim(),
+                leg.destination.trim(),
+                leg.parsed
+                    .map(|date| format.format(date))
+                    .unwrap_or_default()
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn leg(origin: &str, destination: &str, date: &str) -> Leg {
+        Leg {
+            origin: origin.to_string(),
+            destination: destination.to_string(),
+            date: date.to_string(),
+            ..Leg::default()
+        }
+    }
+
+    fn today() -> Date {
+        Date::new(2099, 1, 1).unwrap()
+    }
+
+    #[test]
+    fn books_legs_in_order() {
+        let mut legs = [
+            leg("Paris", "Rome", "1.2.2099"),
+            leg("Rome", "Berlin", "1.2.2099"),
+            leg("Berlin", "Oslo", "2099-02-05"),
+        ];
+
+        assert!(validate(&mut legs, Order::default(), today()).is_ok());
+        assert_eq!(
+            itinerary(&legs, DateFormat::Iso),
+            "1. Paris to Rome on 2099-02-01\n\
+             2. Rome to Berlin on 2099-02-01\n\
+             3. Berlin to Oslo on 2099-02-05"
+        );
+        assert_eq!(earliest(&legs, 2, today()), Date::new(2099, 2, 1).unwrap());
+    }
+
+    #[test]
+    fn errors_point_at_their_leg() {
+        let mut legs = [
+            leg("Paris", "Rome", "5.2.2099"),
+            leg("Rome", "Berlin", "1.2.2099"),
+            leg("Berlin", "berlin", "6.2.2099"),
+            leg("Oslo", "Riga", "1.1.2098"),
+        ];
+
+        let error = validate(&mut legs, Order::default(), today()).unwrap_err();
+        assert_eq!(error.to_string(), "leg 2: the flight leaves before leg 1");
+        assert_eq!(legs[0].error(), None);
+        assert_eq!(
+            legs[2].error(),
+            Some("the flight lands where it leaves from")
+        );
+        assert_eq!(legs[3].error(), Some("the flight leaves in the past"));
+    }
+
+    #[test]
+    fn empty_fields_are_missing_not_wrong() {
+        let mut legs = [leg("Paris", "", "1.2.2099"), leg("", "", "")];
+
+        let error = validate(&mut legs, Order::default(), today()).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "a leg's origin, destination or date is missing"
+        );
+        assert!(legs.iter().all(|leg| leg.error().is_none()));
+    }
+}
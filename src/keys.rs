@@ -0,0 +1,426 @@
+//! Keyboard shortcuts shared by the GUIs, which a config file can rebind.
+//!
+//! Each GUI names its actions and their default shortcuts. The file `keys.json` in the user's config
+//! directory, e.g. `~/.config/iced_7guis/keys.json`, maps GUI and action names to other shortcuts:
+//!
+//! ```json
+//! { "circle_drawer": { "redo": "Ctrl+Y" }, "crud": { "delete": "" } }
+//! ```
+//!
+//! An empty shortcut unbinds the action.
+
+use std::{
+    collections::HashMap,
+    fmt, fs, io,
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::LazyLock,
+};
+
+use iced::{
+    Element,
+    keyboard::{self, Key, Modifiers, key::Named},
+    widget::{column, text},
+};
+
+use crate::{circle_drawer, counter, crud, flight_booker, timer};
+
+const FILE_NAME: &str = "keys.json";
+
+/// A GUI's actions, with their default shortcuts.
+pub type Defaults = [(&'static str, &'static str)];
+
+/// Every GUI with shortcuts, by the name the config file uses for it.
+const GUIS: [(&str, &Defaults); 5] = [
+    ("circle_drawer", circle_drawer::KEYS),
+    ("counter", counter::KEYS),
+    ("crud", crud::KEYS),
+    ("flight_booker", flight_booker::KEYS),
+    ("timer", timer::KEYS),
+];
+
+/// The keymap from the config file, read the first time it is needed, and why the file could not
+/// be used, if it couldn't.
+static KEYMAP: LazyLock<(Keymap, Option<String>)> = LazyLock::new(|| match Keymap::load() {
+    Ok(keymap) => (keymap, None),
+    Err(error) => (
+        Keymap::default(),
+        Some(format!("{error}. The default keys are used.")),
+    ),
+});
+
+/// A key with the modifiers that must be held with it, e.g. "Ctrl+Shift+Z".
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Shortcut {
+    key: Key,
+    modifiers: Modifiers,
+}
+
+impl Shortcut {
+    /// Whether pressing `key` while holding exactly `modifiers` is this shortcut. Letters match
+    /// either case, since shift changes them.
+    #[must_use]
+    pub fn matches(&self, key: &Key, modifiers: Modifiers) -> bool {
+        let held =
+            modifiers & (Modifiers::SHIFT | Modifiers::CTRL | Modifiers::ALT | Modifiers::LOGO);
+
+        held == self.modifiers && self.is_key(key)
+    }
+
+    /// Whether `key` is this shortcut's key, whatever the modifiers.
+    #[must_use]
+    pub fn is_key(&self, key: &Key) -> bool {
+        match (self.key.as_ref(), key.as_ref()) {
+            (Key::Character(a), Key::Character(b)) => a.eq_ignore_ascii_case(b),
+            (a, b) => a == b,
+        }
+    }
+}
+
+impl FromStr for Shortcut {
+    type Err = anyhow::Error;
+
+    /// Parses modifiers and a key joined by `+`. "Ctrl" is Cmd on macOS.
+    fn from_str(shortcut: &str) -> anyhow::Result<Self> {
+        let mut parts: Vec<&str> = shortcut.split('+').map(str::trim).collect();
+        let key = parts.pop().unwrap_or_default();
+
+        let mut modifiers = Modifiers::empty();
+        for part in parts {
+            modifiers |= match part.to_ascii_lowercase().as_str() {
+                "ctrl" | "control" | "cmd" | "command" => Modifiers::COMMAND,
+                "shift" => Modifiers::SHIFT,
+                "alt" | "option" => Modifiers::ALT,
+                "super" | "logo" | "meta" => Modifiers::LOGO,
+                _ => {
+                    return Err(anyhow::Error::msg(format!(
+                        "unknown modifier {part:?} in {shortcut:?}"
+                    )));
+                }
+            };
+        }
+
+        let key = match key.to_ascii_lowercase().as_str() {
+            "space" => Key::Named(Named::Space),
+            "enter" | "return" => Key::Named(Named::Enter),
+            "delete" | "del" => Key::Named(Named::Delete),
+            "backspace" => Key::Named(Named::Backspace),
+            "escape" | "esc" => Key::Named(Named::Escape),
+            "tab" => Key::Named(Named::Tab),
+            character if character.chars().count() == 1 => Key::Character(character.into()),
+            _ => {
+                return Err(anyhow::Error::msg(format!(
+                    "unknown key {key:?} in {shortcut:?}"
+                )));
+            }
+        };
+
+        Ok(Shortcut { key, modifiers })
+    }
+}
+
+impl fmt::Display for Shortcut {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // On macOS Ctrl is Cmd, the logo key, which is then not shown again.
+        let mut held = self.modifiers;
+        for (modifier, name) in [
+            (Modifiers::COMMAND, "Ctrl"),
+            (Modifiers::SHIFT, "Shift"),
+            (Modifiers::ALT, "Alt"),
+            (Modifiers::LOGO, "Super"),
+        ] {
+            if held.contains(modifier) {
+                write!(f, "{name}+")?;
+                held.remove(modifier);
+            }
+        }
+
+        match self.key.as_ref() {
+            Key::Character(character) => f.write_str(&character.to_uppercase()),
+            Key::Named(named) => write!(f, "{named:?}"),
+            Key::Unidentified => f.write_str("?"),
+        }
+    }
+}
+
+/// The shortcuts the config file binds instead of the defaults, by GUI and action. `None` unbinds
+/// an action.
+#[derive(Clone, Debug, Default)]
+pub struct Keymap {
+    guis: HashMap<String, HashMap<String, Option<Shortcut>>>,
+}
+
+impl Keymap {
+    /// The config file in the user's config directory, e.g. `~/.config/iced_7guis/keys.json`.
+    #[must_use]
+    pub fn path() -> Option<PathBuf> {
+        dirs::config_dir().map(|dir| dir.join(env!("CARGO_PKG_NAME")).join(FILE_NAME))
+    }
+
+    /// Reads the keymap from the config file. Without a config directory, nothing is rebound.
+    ///
+    /// # Errors
+    ///
+    /// Errors if the config file can't be read or is not a valid keymap.
+    pub fn load() -> anyhow::Result<Self> {
+        let Some(path) = Keymap::path() else {
+            return Ok(Keymap::default());
+        };
+
+        Keymap::read(&path).map_err(|error| {
+            anyhow::Error::msg(format!("Could not read {}: {error}", path.display()))
+        })
+    }
+
+    /// Reads the keymap at `path`. A missing file rebinds nothing.
+    ///
+    /// # Errors
+    ///
+    /// Errors if the file can't be read or is not a valid keymap.
+    pub fn read(path: &Path) -> anyhow::Result<Self> {
+        match fs::read_to_string(path) {
+            Ok(json) => Keymap::from_json(&json),
+            Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(Keymap::default()),
+            Err(error) => Err(error.into()),
+        }
+    }
+
+    /// Parses a keymap, such as `{ "counter": { "count": "Enter" } }`.
+    ///
+    /// # Errors
+    ///
+    /// Errors if the JSON is invalid, or names a GUI, action or key that doesn't exist.
+    pub fn from_json(json: &str) -> anyhow::Result<Self> {
+        let file: HashMap<String, HashMap<String, String>> = serde_json::from_str(json)?;
+
+        let mut guis = HashMap::new();
+        for (gui, actions) in file {
+            let Some((_, defaults)) = GUIS.iter().find(|(name, _)| *name == gui) else {
+                return Err(anyhow::Error::msg(format!("unknown GUI {gui:?}")));
+            };
+
+            let mut shortcuts = HashMap::new();
+            for (action, shortcut) in actions {
+                if !defaults.iter().any(|(name, _)| *name == action) {
+                    return Err(anyhow::Error::msg(format!(
+                        "unknown action {action:?} for {gui}"
+                    )));
+                }
+
+                let shortcut = if shortcut.is_empty() {
+                    None
+                } else {
+                    Some(shortcut.parse()?)
+                };
+                shortcuts.insert(action, shortcut);
+            }
+            guis.insert(gui, shortcuts);
+        }
+
+        Ok(Keymap { guis })
+    }
+
+    /// The first of `gui`'s actions bound to pressing `key` with `modifiers`, if any.
+    #[must_use]
+    pub fn action(
+        &self,
+        gui: &str,
+        defaults: &Defaults,
+        key: &Key,
+        modifiers: Modifiers,
+    ) -> Option<&'static str> {
+        defaults.iter().find_map(|(action, default)| {
+            self.shortcut(gui, action, default)?
+                .matches(key, modifiers)
+                .then_some(*action)
+        })
+    }
+
+    /// The first of `gui`'s actions bound to `key`, whatever the modifiers, so that letting go of
+    /// a held key ends its action even if a modifier was pressed meanwhile.
+    #[must_use]
+    pub fn released(&self, gui: &str, defaults: &Defaults, key: &Key) -> Option<&'static str> {
+        defaults.iter().find_map(|(action, default)| {
+            self.shortcut(gui, action, default)?
+                .is_key(key)
+                .then_some(*action)
+        })
+    }
+
+    /// The shortcut for `gui`'s `action`, from the config file or else `default`.
+    fn shortcut(&self, gui: &str, action: &str, default: &str) -> Option<Shortcut> {
+        match self.guis.get(gui).and_then(|actions| actions.get(action)) {
+            Some(shortcut) => shortcut.clone(),
+            None => default.parse().ok(),
+        }
+    }
+}
+
+/// The action `gui` binds to `event`, if it is a key press, with the keymap from the config file.
+///
+/// This is meant for [`Subscription::filter_map`](iced::Subscription::filter_map) on
+/// [`keyboard::listen`], which only sees the keys that no widget, such as a focused text input,
+/// used.
+#[must_use]
+pub fn action(gui: &str, defaults: &Defaults, event: &keyboard::Event) -> Option<&'static str> {
+    let keyboard::Event::KeyPressed { key, modifiers, .. } = event else {
+        return None;
+    };
+    KEYMAP.0.action(gui, defaults, key, *modifiers)
+}
+
+/// The action `gui` binds to the key `event` lets go of, if it is a key release, for actions that
+/// last while their key is held.
+#[must_use]
+pub fn released(gui: &str, defaults: &Defaults, event: &keyboard::Event) -> Option<&'static str> {
+    let keyboard::Event::KeyReleased { key, .. } = event else {
+        return None;
+    };
+    KEYMAP.0.released(gui, defaults, key)
+}
+
+/// Why the config file's keymap could not be used, if it couldn't. The default keys are used then.
+#[must_use]
+pub fn error() -> Option<&'static str> {
+    KEYMAP.1.as_deref()
+}
+
+/// `content` under the keymap's [`error`], if there is one, so a broken config file is noticed.
+pub fn with_error<'a, Message: 'a>(content: Element<'a, Message>) -> Element<'a, Message> {
+    match error() {
+        Some(error) => column![text(error).style(text::danger), content]
+            .spacing(10)
+            .into(),
+        None => content,
+    }
+}
+
+/// A GUI's `view` wrapped in [`with_error`], for passing to [`iced::application`].
+pub fn view_with_error<State, Message: 'static>(
+    view: fn(&State) -> Element<'_, Message>,
+) -> impl Fn(&State) -> Element<'_, Message> {
+    move |state| with_error(view(state))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DEFAULTS: &Defaults = &[("undo", "Ctrl+Z"), ("redo", "Ctrl+Shift+Z")];
+
+    fn key(character: &str) -> Key {
+        Key::Character(character.into())
+    }
+
+    #[test]
+    fn parses_and_prints_shortcuts() {
+        let shortcut: Shortcut = "ctrl + shift + z".parse().unwrap();
+        assert_eq!(shortcut.to_string(), "Ctrl+Shift+Z");
+        assert_eq!("Space".parse::<Shortcut>().unwrap().to_string(), "Space");
+
+        let error = "Hyper+Z".parse::<Shortcut>().unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "unknown modifier \"Hyper\" in \"Hyper+Z\""
+        );
+        let error = "Ctrl+".parse::<Shortcut>().unwrap_err();
+        assert_eq!(error.to_string(), "unknown key \"\" in \"Ctrl+\"");
+    }
+
+    #[test]
+    fn matches_exact_modifiers() {
+        let keymap = Keymap::default();
+
+        assert_eq!(
+            keymap.action("circle_drawer", DEFAULTS, &key("z"), Modifiers::COMMAND),
+            Some("undo")
+        );
+        assert_eq!(
+            keymap.action(
+                "circle_drawer",
+                DEFAULTS,
+                &key("Z"),
+                Modifiers::COMMAND | Modifiers::SHIFT
+            ),
+            Some("redo")
+        );
+        assert_eq!(
+            keymap.action("circle_drawer", DEFAULTS, &key("z"), Modifiers::empty()),
+            None
+        );
+    }
+
+    #[test]
+    fn config_rebinds_and_unbinds() {
+        let keymap =
+            Keymap::from_json(r#"{ "circle_drawer": { "redo": "Ctrl+Y", "undo": "" } }"#).unwrap();
+
+        assert_eq!(
+            keymap.action("circle_drawer", DEFAULTS, &key("y"), Modifiers::COMMAND),
+            Some("redo")
+        );
+        for modifiers in [Modifiers::COMMAND, Modifiers::COMMAND | Modifiers::SHIFT] {
+            assert_eq!(
+                keymap.action("circle_drawer", DEFAULTS, &key("z"), modifiers),
+                None
+            );
+        }
+        // Other GUIs keep their defaults.
+        assert_eq!(
+            keymap.action("other", DEFAULTS, &key("z"), Modifiers::COMMAND),
+            Some("undo")
+        );
+    }
+
+    #[test]
+    fn held_actions_can_be_rebound() {
+        let keymap =
+            Keymap::from_json(r#"{ "circle_drawer": { "pan": "P", "delete": "Space" } }"#).unwrap();
+        let space = Key::Named(Named::Space);
+
+        assert_eq!(
+            keymap.action(
+                "circle_drawer",
+                circle_drawer::KEYS,
+                &space,
+                Modifiers::empty()
+            ),
+            Some("delete")
+        );
+        assert_eq!(
+            keymap.action(
+                "circle_drawer",
+                circle_drawer::KEYS,
+                &key("p"),
+                Modifiers::empty()
+            ),
+            Some("pan")
+        );
+        // Shift pressed while panning doesn't keep the pan going.
+        assert_eq!(
+            keymap.released("circle_drawer", circle_drawer::KEYS, &key("P")),
+            Some("pan")
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_names() {
+        let error = Keymap::from_json(r#"{ "paint": {} }"#).unwrap_err();
+        assert_eq!(error.to_string(), "unknown GUI \"paint\"");
+
+        let error = Keymap::from_json(r#"{ "counter": { "reset": "R" } }"#).unwrap_err();
+        assert_eq!(error.to_string(), "unknown action \"reset\" for counter");
+    }
+
+    #[test]
+    fn every_default_parses() {
+        for (gui, defaults) in GUIS {
+            for (action, shortcut) in defaults {
+                assert!(
+                    shortcut.parse::<Shortcut>().is_ok(),
+                    "{gui} {action}: {shortcut}"
+                );
+            }
+        }
+    }
+}
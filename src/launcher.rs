@@ -0,0 +1,178 @@
+//! Every task in one window, with a sidebar to switch between them.
+
+use std::fmt;
+
+use iced::{
+    Element, Length, Subscription, Task,
+    widget::{Column, button, container, row, text},
+};
+
+use crate::{
+    circle_drawer::{self, CircleDrawer},
+    counter::{self, Counter},
+    crud::{self, Crud},
+    flight_booker::{self, FlightBooker},
+    keys,
+    temperature_converter::{self, TemperatureConverter},
+    timer::{self, Timer},
+};
+
+/// Holds the state of every task, so switching tabs loses nothing. It needs
+/// [`iced_aw::ICED_AW_FONT_BYTES`] loaded for the circle drawer.
+#[derive(Debug)]
+pub struct Launcher {
+    tab: Tab,
+    counter: Counter,
+    temperature_converter: TemperatureConverter,
+    flight_booker: FlightBooker,
+    timer: Timer,
+    crud: Crud,
+    circle_drawer: CircleDrawer,
+}
+
+impl Default for Launcher {
+    /// Reads slashed dates in the flight booker in the order of the user's locale, and loads the
+    /// CRUD's names from the user's data directory, as the standalone GUIs do.
+    fn default() -> Self {
+        Launcher {
+            tab: Tab::default(),
+            counter: Counter::default(),
+            temperature_converter: TemperatureConverter::default(),
+            flight_booker: FlightBooker::from_env(),
+            timer: Timer::default(),
+            crud: Crud::load(),
+            circle_drawer: CircleDrawer::default(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Tab {
+    #[default]
+    Counter,
+    TemperatureConverter,
+    FlightBooker,
+    Timer,
+    Crud,
+    CircleDrawer,
+}
+
+impl Tab {
+    pub const ALL: [Tab; 6] = [
+        Tab::Counter,
+        Tab::TemperatureConverter,
+        Tab::FlightBooker,
+        Tab::Timer,
+        Tab::Crud,
+        Tab::CircleDrawer,
+    ];
+}
+
+impl fmt::Display for Tab {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Tab::Counter => "Counter",
+                Tab::TemperatureConverter => "Temperature Converter",
+                Tab::FlightBooker => "Flight Booker",
+                Tab::Timer => "Timer",
+                Tab::Crud => "CRUD",
+                Tab::CircleDrawer => "Circle Drawer",
+            }
+        )
+    }
+}
+
+#[derive(Clone, Debug)]
+pub enum Message {
+    TabSelected(Tab),
+    Counter(counter::Message),
+    TemperatureConverter(temperature_converter::Message),
+    FlightBooker(flight_booker::Message),
+    Timer(timer::Message),
+    Crud(crud::Message),
+    CircleDrawer(circle_drawer::Message),
+}
+
+impl Launcher {
+    #[must_use]
+    pub fn tab(&self) -> Tab {
+        self.tab
+    }
+
+    pub fn update(&mut self, message: Message) -> Task<Message> {
+        match message {
+            Message::TabSelected(tab) => self.tab = tab,
+            Message::Counter(message) => self.counter.update(message),
+            Message::TemperatureConverter(message) => self.temperature_converter.update(message),
+            Message::FlightBooker(message) => self.flight_booker.update(message),
+            Message::Timer(message) => self.timer.update(message),
+            Message::Crud(message) => self.crud.update(message),
+            Message::CircleDrawer(message) => {
+                return self
+                    .circle_drawer
+                    .update(message)
+                    .map(Message::CircleDrawer);
+            }
+        }
+
+        Task::none()
+    }
+
+    #[must_use]
+    pub fn view(&self) -> Element<'_, Message> {
+        let mut sidebar = Column::new().padding(10).spacing(10).width(200);
+        for tab in Tab::ALL {
+            let style = if tab == self.tab {
+                button::primary
+            } else {
+                button::secondary
+            };
+
+            sidebar = sidebar.push(
+                button(text(tab.to_string()))
+                    .on_press(Message::TabSelected(tab))
+                    .style(style)
+                    .width(Length::Fill),
+            );
+        }
+
+        let content = match self.tab {
+            Tab::Counter => self.counter.view().map(Message::Counter),
+            Tab::TemperatureConverter => self
+                .temperature_converter
+                .view()
+                .map(Message::TemperatureConverter),
+            Tab::FlightBooker => self.flight_booker.view().map(Message::FlightBooker),
+            Tab::Timer => self.timer.view().map(Message::Timer),
+            Tab::Crud => self.crud.view().map(Message::Crud),
+            Tab::CircleDrawer => self.circle_drawer.view().map(Message::CircleDrawer),
+        };
+
+        row![
+            container(sidebar)
+                .style(container::rounded_box)
+                .height(Length::Fill),
+            container(keys::with_error(content))
+                .width(Length::Fill)
+                .height(Length::Fill),
+        ]
+        .into()
+    }
+
+    /// Keeps the timer running whichever tab is shown, and passes keys to the shown tab only.
+    pub fn subscription(&self) -> Subscription<Message> {
+        let keys = match self.tab {
+            Tab::Counter => self.counter.subscription().map(Message::Counter),
+            Tab::TemperatureConverter => Subscription::none(),
+            Tab::FlightBooker => self.flight_booker.subscription().map(Message::FlightBooker),
+            Tab::Timer => self.timer.shortcuts().map(Message::Timer),
+            Tab::Crud => self.crud.subscription().map(Message::Crud),
+            Tab::CircleDrawer => self.circle_drawer.subscription().map(Message::CircleDrawer),
+        };
+
+        Subscription::batch([Timer::run_timer(&self.timer).map(Message::Timer), keys])
+    }
+}
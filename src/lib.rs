@@ -0,0 +1,15 @@
+//! An implementation of [7 GUIs](https://eugenkiss.github.io/7guis/) using [iced](https://iced.rs/).
+//!
+//! Each task's state, messages and `update`/`view` logic live in their own module, so they can be
+//! embedded in other applications. The binaries in `src/bin` only launch them.
+
+pub mod cells;
+pub mod circle_drawer;
+pub mod counter;
+pub mod crud;
+pub mod date_picker;
+pub mod flight_booker;
+pub mod keys;
+pub mod launcher;
+pub mod temperature_converter;
+pub mod timer;
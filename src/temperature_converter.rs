@@ -0,0 +1,109 @@
+//! Temperature Converter: two text fields that convert between Celsius and Fahrenheit.
+
+use iced::widget::{row, text, text_input};
+use iced::{Alignment, Element};
+
+#[derive(Debug, Default)]
+pub struct TemperatureConverter {
+    celsius: String,
+    fahrenheit: String,
+}
+
+#[derive(Clone, Debug)]
+pub enum Message {
+    CelsiusChanged(String),
+    FahrenheitChanged(String),
+}
+
+impl TemperatureConverter {
+    #[must_use]
+    pub fn celsius(&self) -> &str {
+        &self.celsius
+    }
+
+    #[must_use]
+    pub fn fahrenheit(&self) -> &str {
+        &self.fahrenheit
+    }
+
+    fn empty(&mut self) {
+        self.celsius = String::new();
+        self.fahrenheit = String::new();
+    }
+
+    pub fn update(&mut self, message: Message) {
+        match message {
+            Message::CelsiusChanged(s) => {
+                if s.is_empty() {
+                    self.empty();
+                } else {
+                    self.celsius.clone_from(&s);
+                    match s.parse::<f64>() {
+                        Ok(celsius) => {
+                            let fahrenheit = celsius * (9. / 5.) + 32.;
+                            self.fahrenheit = fahrenheit.to_string();
+                        }
+                        Err(_) => self.empty(),
+                    }
+                }
+            }
+            Message::FahrenheitChanged(s) => {
+                if s.is_empty() {
+                    self.empty();
+                } else {
+                    self.fahrenheit.clone_from(&s);
+                    match s.parse::<f64>() {
+                        Ok(fahrenheit) => {
+                            let celsius = (fahrenheit - 32.) * (5. / 9.);
+                            self.celsius = celsius.to_string();
+                        }
+                        Err(_) => self.empty(),
+                    }
+                }
+            }
+        }
+    }
+
+    #[must_use]
+    pub fn view(&self) -> Element<'_, Message> {
+        row![
+            text_input("", &self.celsius)
+                .id("celsius")
+                .on_input(Message::CelsiusChanged),
+            text(" Celsius = "),
+            text_input("", &self.fahrenheit)
+                .id("fahrenheit")
+                .on_input(Message::FahrenheitChanged),
+            text(" Fahrenheit"),
+        ]
+        .padding(20)
+        .align_y(Alignment::Center)
+        .into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn converts_both_ways() {
+        let mut converter = TemperatureConverter::default();
+
+        converter.update(Message::CelsiusChanged("100".to_string()));
+        assert_eq!(converter.fahrenheit(), "212");
+
+        converter.update(Message::FahrenheitChanged("32".to_string()));
+        assert_eq!(converter.celsius(), "0");
+    }
+
+    #[test]
+    fn clears_on_invalid_input() {
+        let mut converter = TemperatureConverter::default();
+        converter.update(Message::CelsiusChanged("5".to_string()));
+        converter.update(Message::CelsiusChanged("5x".to_string()));
+
+        assert_eq!(converter.celsius(), "");
+        assert_eq!(converter.fahrenheit(), "");
+    }
+}
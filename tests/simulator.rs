@@ -0,0 +1,308 @@
+//! Drives each GUI through `iced_test`'s headless simulator, as a user would.
+
+use iced::{Element, Event, Point, mouse};
+use iced_7guis::{
+    circle_drawer::{self, CircleDrawer},
+    counter::Counter,
+    crud::{self, Crud, storage::Storage},
+    flight_booker::{self, FlightBooker},
+    temperature_converter::{self, TemperatureConverter},
+    timer::{self, Timer},
+};
+use iced_test::{Error, Simulator, selector, simulator};
+
+/// Runs `interact` against a fresh view of `app`, then feeds the produced messages back to it.
+/// Whatever `update` returns, such as a task, is dropped.
+fn interact<App, Message, Output>(
+    app: &mut App,
+    view: impl Fn(&App) -> Element<'_, Message>,
+    update: impl Fn(&mut App, Message) -> Output,
+    interact: impl FnOnce(&mut Simulator<'_, Message>) -> Result<(), Error>,
+) -> Result<(), Error> {
+    let mut ui = simulator(view(app));
+    interact(&mut ui)?;
+
+    let messages: Vec<Message> = ui.into_messages().collect();
+    for message in messages {
+        update(app, message);
+    }
+
+    Ok(())
+}
+
+fn press(ui: &mut Simulator<'_, impl Sized>, button: mouse::Button, point: Point) {
+    ui.point_at(point);
+    ui.simulate([
+        Event::Mouse(mouse::Event::CursorMoved { position: point }),
+        Event::Mouse(mouse::Event::ButtonPressed(button)),
+        Event::Mouse(mouse::Event::ButtonReleased(button)),
+    ]);
+}
+
+#[test]
+fn counter_counts() -> Result<(), Error> {
+    let mut app = Counter::default();
+
+    for _ in 0..2 {
+        interact(&mut app, Counter::view, Counter::update, |ui| {
+            ui.click("Count").map(|_| ())
+        })?;
+    }
+
+    assert_eq!(app.value(), 2);
+    assert!(simulator(app.view()).find("2").is_ok());
+
+    Ok(())
+}
+
+#[test]
+fn temperature_converter_converts_celsius() -> Result<(), Error> {
+    let mut app = TemperatureConverter::default();
+
+    interact(
+        &mut app,
+        TemperatureConverter::view,
+        TemperatureConverter::update,
+        |ui| {
+            ui.click(selector::id("celsius"))?;
+            ui.typewrite("100");
+            Ok(())
+        },
+    )?;
+
+    assert_eq!(app.celsius(), "100");
+    assert_eq!(app.fahrenheit(), "212");
+    assert!(simulator(app.view()).find("212").is_ok());
+
+    app.update(temperature_converter::Message::FahrenheitChanged(
+        "-40".to_string(),
+    ));
+    assert_eq!(app.celsius(), "-40");
+    Ok(())
+}
+
+#[test]
+fn flight_booker_books_return_flight() -> Result<(), Error> {
+    let mut app = FlightBooker::default();
+    app.update(flight_booker::Message::FlightSelected(
+        flight_booker::Flight::Return,
+    ));
+
+    interact(&mut app, FlightBooker::view, FlightBooker::update, |ui| {
+        ui.click(selector::id("one-way flight"))?;
+        ui.typewrite("1.2.2099");
+        ui.click(selector::id("one-way time"))?;
+        ui.typewrite("9:30");
+        ui.click(selector::id("return flight"))?;
+        ui.typewrite("3.2.2099");
+        ui.click(selector::id("return time"))?;
+        ui.typewrite("18:00");
+        Ok(())
+    })?;
+
+    interact(&mut app, FlightBooker::view, FlightBooker::update, |ui| {
+        ui.click("Book").map(|_| ())
+    })?;
+
+    let dialogue = "You have booked a flight leaving on 1.2.2099 at 09:30 UTC and returning on \
+                    3.2.2099 at 18:00 UTC";
+    assert_eq!(app.dialogue(), Some(dialogue));
+    assert!(simulator(app.view()).find(dialogue).is_ok());
+    Ok(())
+}
+
+#[test]
+fn flight_booker_shows_errors_on_the_return_field() -> Result<(), Error> {
+    let mut app = FlightBooker::default();
+    app.update(flight_booker::Message::FlightSelected(
+        flight_booker::Flight::Return,
+    ));
+
+    interact(&mut app, FlightBooker::view, FlightBooker::update, |ui| {
+        ui.click(selector::id("one-way flight"))?;
+        ui.typewrite("3.2.2099");
+        ui.click(selector::id("one-way time"))?;
+        ui.typewrite("12:00");
+        ui.click(selector::id("return flight"))?;
+        ui.typewrite("1.2.2099");
+        ui.click(selector::id("return time"))?;
+        ui.typewrite("12:00");
+        Ok(())
+    })?;
+
+    let error = "the return flight leaves before the flight";
+    assert_eq!(app.return_error(), Some(error));
+    assert!(simulator(app.view()).find(error).is_ok());
+    Ok(())
+}
+
+#[test]
+fn flight_booker_books_a_multi_city_trip() -> Result<(), Error> {
+    let mut app = FlightBooker::default();
+    app.update(flight_booker::Message::FlightSelected(
+        flight_booker::Flight::MultiCity,
+    ));
+
+    interact(&mut app, FlightBooker::view, FlightBooker::update, |ui| {
+        for (leg, fields) in [
+            (1, ["Paris", "Rome", "1.2.2099"]),
+            (2, ["Rome", "Berlin", "1.2.2099"]),
+        ] {
+            for (field, value) in ["origin", "destination", "date"].into_iter().zip(fields) {
+                ui.click(selector::id(format!("leg {leg} {field}")))?;
+                ui.typewrite(value);
+            }
+        }
+        Ok(())
+    })?;
+
+    interact(&mut app, FlightBooker::view, FlightBooker::update, |ui| {
+        ui.click("Book").map(|_| ())
+    })?;
+
+    let dialogue = "You have booked a multi-city trip:\n1. Paris to Rome on 1.2.2099\n\
+                    2. Rome to Berlin on 1.2.2099";
+    assert_eq!(app.dialogue(), Some(dialogue));
+    assert!(simulator(app.view()).find(dialogue).is_ok());
+    Ok(())
+}
+
+#[test]
+fn timer_slider_sets_duration() -> Result<(), Error> {
+    let mut app = Timer::default();
+
+    interact(&mut app, Timer::view, Timer::update, |ui| {
+        let label = ui.find("Duration: ")?.bounds();
+        let y = label.center_y();
+
+        ui.point_at(Point::new(label.x + label.width + 20.0, y));
+        ui.simulate([Event::Mouse(mouse::Event::ButtonPressed(
+            mouse::Button::Left,
+        ))]);
+
+        let end = Point::new(10_000.0, y);
+        ui.point_at(end);
+        ui.simulate([
+            Event::Mouse(mouse::Event::CursorMoved { position: end }),
+            Event::Mouse(mouse::Event::ButtonReleased(mouse::Button::Left)),
+        ]);
+        Ok(())
+    })?;
+
+    assert!((app.duration_max() - 60.0).abs() < f32::EPSILON);
+
+    app.update(timer::Message::Reset);
+    assert!(simulator(app.view()).find("0.0s").is_ok());
+    Ok(())
+}
+
+#[test]
+fn crud_creates_updates_and_deletes() -> Result<(), Error> {
+    let mut app = Crud::default();
+
+    interact(&mut app, Crud::view, Crud::update, |ui| {
+        ui.click(selector::id("name"))?;
+        ui.typewrite("John");
+        ui.click(selector::id("surname"))?;
+        ui.typewrite("Doe");
+        Ok(())
+    })?;
+    interact(&mut app, Crud::view, Crud::update, |ui| {
+        ui.click("Create").map(|_| ())
+    })?;
+    let john = app.people()[0].clone();
+    assert_eq!(john.to_string(), "Doe, John");
+
+    // The selection list lays out its entries without screen positions, so it can be searched
+    // but not clicked.
+    assert!(simulator(app.view()).find("Doe, John").is_ok());
+    app.update(crud::Message::PersonSelected(0, john.clone()));
+
+    interact(&mut app, Crud::view, Crud::update, |ui| {
+        ui.click(selector::id("name"))?;
+        ui.typewrite("ny");
+        Ok(())
+    })?;
+    interact(&mut app, Crud::view, Crud::update, |ui| {
+        ui.click("Update").map(|_| ())
+    })?;
+    assert_eq!(app.people()[0].id, john.id);
+    assert_eq!(app.people()[0].to_string(), "Doe, Johnny");
+
+    interact(&mut app, Crud::view, Crud::update, |ui| {
+        ui.click("Delete").map(|_| ())
+    })?;
+    assert!(app.people().is_empty());
+    Ok(())
+}
+
+#[test]
+fn crud_autosaves_and_reports_corrupt_files() -> Result<(), Error> {
+    let dir = tempfile::tempdir().expect("create a temporary directory");
+    let path = dir.path().join("crud.json");
+
+    let mut app = Crud::with_storage(Storage::new(&path));
+    app.update(crud::Message::NameChanged("John".to_string()));
+    app.update(crud::Message::SurnameChanged("Doe".to_string()));
+    interact(&mut app, Crud::view, Crud::update, |ui| {
+        ui.click("Create").map(|_| ())
+    })?;
+    assert_eq!(
+        Crud::with_storage(Storage::new(&path)).people(),
+        app.people()
+    );
+
+    std::fs::write(&path, "not json").expect("write corrupt file");
+    let app = Crud::with_storage(Storage::new(&path));
+    assert!(app.people().is_empty());
+
+    let error = app.error().expect("an error is shown").to_string();
+    assert!(error.starts_with("Could not load"));
+    assert!(simulator(app.view()).find(error.as_str()).is_ok());
+    Ok(())
+}
+
+#[test]
+fn circle_drawer_draws_and_undoes() -> Result<(), Error> {
+    let mut app = CircleDrawer::default();
+
+    interact(&mut app, CircleDrawer::view, CircleDrawer::update, |ui| {
+        press(ui, mouse::Button::Left, Point::new(300.0, 300.0));
+        Ok(())
+    })?;
+
+    let circles = app.circles();
+    assert_eq!(circles.len(), 1);
+    assert!((circles[0].radius - 50.0).abs() < f32::EPSILON);
+
+    interact(&mut app, CircleDrawer::view, CircleDrawer::update, |ui| {
+        ui.click("Undo").map(|_| ())
+    })?;
+    assert!(app.circles().is_empty());
+
+    interact(&mut app, CircleDrawer::view, CircleDrawer::update, |ui| {
+        ui.click("Redo").map(|_| ())
+    })?;
+    assert_eq!(app.circles().len(), 1);
+
+    interact(&mut app, CircleDrawer::view, CircleDrawer::update, |ui| {
+        let center = Point::new(310.0, 310.0);
+        ui.point_at(center);
+        ui.simulate([Event::Mouse(mouse::Event::CursorMoved { position: center })]);
+        press(ui, mouse::Button::Right, center);
+        Ok(())
+    })?;
+    assert_eq!(app.selected().len(), 1);
+    assert!(simulator(app.view()).find("Circle Radius 50").is_ok());
+
+    let _ = app.update(circle_drawer::Message::SizeChange(20.0));
+    let _ = app.update(circle_drawer::Message::SizeReleased);
+    assert!((app.circles()[0].radius - 20.0).abs() < f32::EPSILON);
+    assert!(app.to_svg().contains("r=\"20\" fill=\"#ffffff\""));
+
+    interact(&mut app, CircleDrawer::view, CircleDrawer::update, |ui| {
+        ui.click("Undo").map(|_| ())
+    })?;
+    assert!((app.circles()[0].radius - 50.0).abs() < f32::EPSILON);
+    Ok(())
+}